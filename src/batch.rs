@@ -0,0 +1,98 @@
+//! `web add --stdin`: lines of `alias url [tags...]`, and names for lines that give only a URL.
+
+/// At most this many words of a page title go into an alias name.
+const TITLE_WORDS: usize = 3;
+
+/// One line of input to `web add --stdin`.
+pub struct Line {
+    /// `None` when the line is just a URL
+    pub alias: Option<String>,
+    pub url: String,
+    pub tags: Vec<String>,
+}
+
+/// Split `line` into alias, URL and tags; tags may also be comma-separated.
+pub fn parse_line(line: &str) -> Line {
+    let mut words = line.split_whitespace().map(String::from);
+    let first = words.next().unwrap_or_default();
+    let Some(url) = words.next() else {
+        return Line {
+            alias: None,
+            url: first,
+            tags: vec![],
+        };
+    };
+    let tags = words
+        .flat_map(|w| w.split(',').map(String::from).collect::<Vec<_>>())
+        .filter(|t| !t.is_empty())
+        .collect();
+    Line {
+        alias: Some(first),
+        url,
+        tags,
+    }
+}
+
+/// A name for `url`: the first words of the page `title` (before a " - Site name" suffix),
+/// or else the host without `www.` and the top-level domain, e.g. `news-ycombinator`.
+pub fn derive_alias(url: &str, title: Option<&str>) -> Option<String> {
+    let from_title = title.map(|title| {
+        let title = [" - ", " | ", " · ", " — ", ": "]
+            .iter()
+            .filter_map(|sep| title.split_once(sep).map(|(head, _)| head))
+            .min_by_key(|head| head.len())
+            .unwrap_or(title);
+        slug(title, TITLE_WORDS)
+    });
+    if let Some(name) = from_title.filter(|name| !name.is_empty()) {
+        return Some(name);
+    }
+    let host = url::Url::parse(url).ok()?.host_str()?.to_string();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let labels: Vec<&str> = host.split('.').collect();
+    let labels = match labels.len() {
+        1 => &labels[..],
+        n => &labels[..n - 1],
+    };
+    Some(slug(&labels.join(" "), labels.len())).filter(|name| !name.is_empty())
+}
+
+/// The first `words` words of `text`, lowercased and joined with `-`.
+fn slug(text: &str, words: usize) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(words)
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_line_splits_alias_url_and_tags() {
+        let line = parse_line("  gh https://github.com work,code  dev ");
+        assert_eq!(line.alias.as_deref(), Some("gh"));
+        assert_eq!(line.url, "https://github.com");
+        assert_eq!(line.tags, ["work", "code", "dev"]);
+
+        let line = parse_line("https://github.com");
+        assert_eq!(line.alias, None);
+        assert_eq!(line.url, "https://github.com");
+    }
+
+    #[test]
+    fn derive_alias_prefers_the_title() {
+        assert_eq!(
+            derive_alias("https://doc.rust-lang.org", Some("The Rust Book - Rust")).as_deref(),
+            Some("the-rust-book")
+        );
+        assert_eq!(
+            derive_alias("https://www.news.ycombinator.com/item", None).as_deref(),
+            Some("news-ycombinator")
+        );
+        assert_eq!(derive_alias("not a url", Some(" - ")), None);
+    }
+}
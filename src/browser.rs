@@ -1,30 +1,1173 @@
-use anyhow::Result;
-use std::process::Command;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
 
+use crate::cdp;
 use crate::cli::BrowserChoice;
+use crate::config::{self, Config};
+use crate::trace::trace;
 
-pub fn open_url(url: &str, browser: BrowserChoice) -> Result<()> {
-    let mut cmd = Command::new("open");
-    match browser {
-        BrowserChoice::Default => {
-            cmd.arg(url);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    MacOs,
+    Linux,
+    /// Linux running under Windows Subsystem for Linux
+    Wsl,
+}
+
+impl Platform {
+    pub fn detect() -> Self {
+        if cfg!(target_os = "macos") {
+            Platform::MacOs
+        } else if is_wsl() {
+            Platform::Wsl
+        } else {
+            Platform::Linux
+        }
+    }
+}
+
+fn is_wsl() -> bool {
+    if std::env::var_os("WSL_DISTRO_NAME").is_some() {
+        return true;
+    }
+    std::fs::read_to_string("/proc/version")
+        .map(|v| v.to_lowercase().contains("microsoft"))
+        .unwrap_or(false)
+}
+
+/// Look up an executable by name in `$PATH`.
+pub fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Engine family of a browser, which determines the command-line flags it understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Family {
+    Chromium,
+    Firefox,
+    Safari,
+}
+
+/// A browser `web` knows how to launch without any configuration.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BrowserSpec {
+    /// Name used with `--browser` and in the config
+    pub name: &'static str,
+    /// Other accepted spellings of the name
+    pub aliases: &'static [&'static str],
+    pub family: Family,
+    /// Application name passed to `open -a` on macOS
+    pub macos_app: Option<&'static str>,
+    /// Candidate executables on Linux, in order of preference
+    pub linux_cmds: &'static [&'static str],
+    /// Flatpak application ID, used when none of `linux_cmds` is installed
+    pub flatpak_id: Option<&'static str>,
+    /// Snap package name, used when neither a binary nor a Flatpak is installed
+    pub snap: Option<&'static str>,
+}
+
+pub const BUILTIN_BROWSERS: &[BrowserSpec] = &[
+    BrowserSpec {
+        name: "safari",
+        aliases: &[],
+        family: Family::Safari,
+        macos_app: Some("Safari"),
+        linux_cmds: &[],
+        flatpak_id: None,
+        snap: None,
+    },
+    BrowserSpec {
+        name: "chrome",
+        aliases: &["google-chrome", "google chrome"],
+        family: Family::Chromium,
+        macos_app: Some("Google Chrome"),
+        linux_cmds: &["google-chrome", "google-chrome-stable"],
+        flatpak_id: Some("com.google.Chrome"),
+        snap: None,
+    },
+    BrowserSpec {
+        name: "firefox",
+        aliases: &[],
+        family: Family::Firefox,
+        macos_app: Some("Firefox"),
+        linux_cmds: &["firefox"],
+        flatpak_id: Some("org.mozilla.firefox"),
+        snap: Some("firefox"),
+    },
+    BrowserSpec {
+        name: "brave",
+        aliases: &["brave-browser", "brave browser"],
+        family: Family::Chromium,
+        macos_app: Some("Brave Browser"),
+        linux_cmds: &["brave-browser", "brave"],
+        flatpak_id: Some("com.brave.Browser"),
+        snap: Some("brave"),
+    },
+    BrowserSpec {
+        name: "edge",
+        aliases: &["microsoft-edge", "microsoft edge", "msedge"],
+        family: Family::Chromium,
+        macos_app: Some("Microsoft Edge"),
+        linux_cmds: &["microsoft-edge", "microsoft-edge-stable"],
+        flatpak_id: Some("com.microsoft.Edge"),
+        snap: None,
+    },
+    BrowserSpec {
+        name: "arc",
+        aliases: &[],
+        family: Family::Chromium,
+        macos_app: Some("Arc"),
+        linux_cmds: &[],
+        flatpak_id: None,
+        snap: None,
+    },
+    BrowserSpec {
+        name: "vivaldi",
+        aliases: &[],
+        family: Family::Chromium,
+        macos_app: Some("Vivaldi"),
+        linux_cmds: &["vivaldi", "vivaldi-stable"],
+        flatpak_id: Some("com.vivaldi.Vivaldi"),
+        snap: Some("vivaldi"),
+    },
+    BrowserSpec {
+        name: "opera",
+        aliases: &[],
+        family: Family::Chromium,
+        macos_app: Some("Opera"),
+        linux_cmds: &["opera"],
+        flatpak_id: Some("com.opera.Opera"),
+        snap: Some("opera"),
+    },
+    BrowserSpec {
+        name: "librewolf",
+        aliases: &[],
+        family: Family::Firefox,
+        macos_app: Some("LibreWolf"),
+        linux_cmds: &["librewolf"],
+        flatpak_id: Some("io.gitlab.librewolf-community"),
+        snap: None,
+    },
+    BrowserSpec {
+        name: "chromium",
+        aliases: &["chromium-browser"],
+        family: Family::Chromium,
+        macos_app: Some("Chromium"),
+        linux_cmds: &["chromium", "chromium-browser"],
+        flatpak_id: Some("org.chromium.Chromium"),
+        snap: Some("chromium"),
+    },
+    BrowserSpec {
+        name: "zen",
+        aliases: &["zen-browser", "zen browser"],
+        family: Family::Firefox,
+        macos_app: Some("Zen"),
+        linux_cmds: &["zen-browser", "zen"],
+        flatpak_id: Some("app.zen_browser.zen"),
+        snap: None,
+    },
+];
+
+/// Look up a built-in browser by name or alternative spelling, ignoring case.
+pub fn builtin_browser(name: &str) -> Option<&'static BrowserSpec> {
+    let name = name.trim().to_lowercase();
+    BUILTIN_BROWSERS
+        .iter()
+        .find(|spec| spec.name == name || spec.aliases.contains(&name.as_str()))
+}
+
+/// `flatpak run`/`snap run` command for a browser only installed as a Flatpak or Snap.
+fn sandboxed_command(spec: &BrowserSpec) -> Option<Vec<String>> {
+    if let Some(id) = spec.flatpak_id {
+        if flatpak_installed(id) && find_in_path("flatpak").is_some() {
+            return Some(vec!["flatpak".into(), "run".into(), id.into()]);
+        }
+    }
+    if let Some(name) = spec.snap {
+        if std::path::Path::new("/snap/bin").join(name).exists() {
+            return Some(vec!["snap".into(), "run".into(), name.into()]);
+        }
+    }
+    None
+}
+
+/// Whether a Flatpak app is installed system-wide or for the current user.
+fn flatpak_installed(id: &str) -> bool {
+    let mut roots = vec![PathBuf::from("/var/lib/flatpak")];
+    if let Some(data) = dirs::data_dir() {
+        roots.push(data.join("flatpak"));
+    }
+    roots
+        .iter()
+        .any(|root| root.join("app").join(id).join("current").exists())
+}
+
+/// Look up a macOS application bundle in the usual install locations.
+fn find_macos_app(app: &str) -> Option<PathBuf> {
+    if app.contains('/') {
+        return Some(PathBuf::from(app)).filter(|bundle| bundle.exists());
+    }
+    let mut dirs = vec![
+        PathBuf::from("/Applications"),
+        PathBuf::from("/System/Applications"),
+    ];
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join("Applications"));
+    }
+    dirs.into_iter()
+        .map(|dir| dir.join(format!("{app}.app")))
+        .find(|bundle| bundle.exists())
+}
+
+/// Text browsers tried in order when none is configured.
+const TEXT_BROWSERS: &[&str] = &["w3m", "lynx", "browsh", "elinks", "links"];
+
+/// Command running a text browser in the current terminal.
+fn text_browser_command(config: &Config, name: Option<&str>, url: &str) -> Result<Command> {
+    let configured = match name {
+        Some(name) => Some(name.to_string()),
+        None => config.text_browser.clone(),
+    };
+    let name = match configured {
+        Some(name) => name,
+        None => TEXT_BROWSERS
+            .iter()
+            .find(|b| find_in_path(b).is_some())
+            .map(|b| b.to_string())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No text browser found (looked for {}); set text_browser in the config",
+                    TEXT_BROWSERS.join(", ")
+                )
+            })?,
+    };
+    let mut parts = name.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("text_browser is empty"))?;
+    let mut cmd = Command::new(program);
+    cmd.args(parts).arg(url);
+    Ok(cmd)
+}
+
+/// Options that change how the URL is opened, independent of the browser.
+#[derive(Debug, Clone, Default)]
+pub struct OpenOptions {
+    /// Open in a private/incognito window
+    pub private: bool,
+    /// Force a new browser window
+    pub new_window: bool,
+    /// Force a new tab in the current window
+    pub new_tab: bool,
+    /// Browser profile to open in
+    pub profile: Option<String>,
+    /// Start a separate browser process instead of reusing a running one (Firefox)
+    pub new_instance: bool,
+    /// Open as a chromeless app window (Chromium `--app`)
+    pub app: bool,
+    /// Open fullscreen in kiosk mode (Chromium `--kiosk`)
+    pub kiosk: bool,
+    /// Don't bring the browser to the foreground (macOS only)
+    pub background: bool,
+    /// Block until the browser exits
+    pub wait: bool,
+    /// Extra arguments passed to the browser after the URL
+    pub extra_args: Vec<String>,
+    /// Focus a tab already showing the URL instead of opening another (macOS)
+    pub activate_existing: bool,
+    /// Open through the DevTools protocol of a browser listening on this port
+    pub cdp_port: Option<u16>,
+    /// Open even URLs whose scheme is blocked as dangerous
+    pub force: bool,
+    /// Print the command instead of running it
+    pub dry_run: bool,
+}
+
+impl OpenOptions {
+    /// Whether any option needs the browser to be known (the system opener ignores them).
+    fn needs_browser(&self) -> bool {
+        self.private
+            || self.new_window
+            || self.new_tab
+            || self.profile.is_some()
+            || self.new_instance
+            || self.app
+            || self.kiosk
+            || !self.extra_args.is_empty()
+    }
+}
+
+/// How a selected browser is launched on each platform.
+struct Launcher {
+    name: String,
+    family: Option<Family>,
+    macos_app: Option<String>,
+    linux_cmd: Option<Vec<String>>,
+}
+
+impl Launcher {
+    fn from_spec(spec: &BrowserSpec) -> Self {
+        let linux_cmd = spec
+            .linux_cmds
+            .iter()
+            .find(|cmd| find_in_path(cmd).is_some())
+            .map(|cmd| vec![cmd.to_string()])
+            .or_else(|| sandboxed_command(spec))
+            // Nothing installed: keep the usual binary name so the error names it.
+            .or_else(|| spec.linux_cmds.first().map(|cmd| vec![cmd.to_string()]));
+        Launcher {
+            name: spec.name.to_string(),
+            family: Some(spec.family),
+            macos_app: spec.macos_app.map(str::to_string),
+            linux_cmd,
+        }
+    }
+
+    /// `None` means the system default browser. Browsers defined in the config take
+    /// precedence over built-in ones of the same name.
+    fn for_choice(config: &Config, browser: &BrowserChoice) -> Result<Option<Self>> {
+        let BrowserChoice::Named(name) = browser else {
+            return Ok(None);
+        };
+        if let Some(custom) = config.browsers.get(name).cloned() {
+            return Ok(Some(Launcher {
+                name: name.clone(),
+                family: custom.family,
+                macos_app: custom.macos_app,
+                linux_cmd: custom
+                    .linux_cmd
+                    .map(|c| c.split_whitespace().map(str::to_string).collect())
+                    .filter(|parts: &Vec<String>| !parts.is_empty()),
+            }));
         }
-        BrowserChoice::Safari => {
-            cmd.args(["-a", "Safari", url]);
+        let spec = builtin_browser(name).ok_or_else(|| {
+            let names: Vec<&str> = BUILTIN_BROWSERS.iter().map(|b| b.name).collect();
+            anyhow::anyhow!(
+                "Unknown browser '{}' (built in: {}; others can be defined in [browsers] of the config)",
+                name,
+                names.join(", ")
+            )
+        })?;
+        let mut launcher = Launcher::from_spec(spec);
+        if let Some(path) = config.browser_paths.get(spec.name) {
+            // On macOS the override names an application (bundle path or app name).
+            match Platform::detect() {
+                Platform::MacOs => launcher.macos_app = Some(path.clone()),
+                Platform::Linux | Platform::Wsl => launcher.linux_cmd = Some(vec![path.clone()]),
+            }
         }
-        BrowserChoice::Chrome => {
-            cmd.args(["-a", "Google Chrome", url]);
+        Ok(Some(launcher))
+    }
+
+    fn is_installed(&self, platform: Platform) -> bool {
+        match platform {
+            Platform::MacOs => self
+                .macos_app
+                .as_deref()
+                .is_some_and(|app| find_macos_app(app).is_some()),
+            Platform::Linux | Platform::Wsl => self
+                .linux_cmd
+                .as_ref()
+                .is_some_and(|parts| find_in_path(&parts[0]).is_some()),
         }
-        BrowserChoice::Firefox => {
-            cmd.args(["-a", "Firefox", url]);
+    }
+
+    fn require_chromium(&self, feature: &str) -> Result<()> {
+        if self.family != Some(Family::Chromium) {
+            anyhow::bail!(
+                "{} is only supported for Chromium-based browsers, not '{}'",
+                feature,
+                self.name
+            );
+        }
+        Ok(())
+    }
+
+    /// Browser arguments for opening `url` with `opts`, ending with the URL itself.
+    fn args(&self, url: &str, opts: &OpenOptions) -> Result<Vec<String>> {
+        let mut args = Vec::new();
+        if let Some(profile) = &opts.profile {
+            match self.family {
+                Some(Family::Chromium) => args.push(format!("--profile-directory={}", profile)),
+                Some(Family::Firefox) => args.extend(["-P".to_string(), profile.clone()]),
+                _ => anyhow::bail!(
+                    "Profile selection is only supported for Chromium- and Firefox-based browsers, not '{}'",
+                    self.name
+                ),
+            }
+        }
+        if opts.new_instance {
+            if self.family != Some(Family::Firefox) {
+                anyhow::bail!(
+                    "--new-instance is only supported for Firefox-based browsers, not '{}'",
+                    self.name
+                );
+            }
+            args.push("--new-instance".to_string());
+        }
+        if opts.kiosk {
+            self.require_chromium("Kiosk mode")?;
+            args.push("--kiosk".to_string());
+        }
+        if opts.private {
+            let flag = match self.family {
+                Some(Family::Chromium) => "--incognito",
+                Some(Family::Firefox) => "--private-window",
+                Some(Family::Safari) => {
+                    anyhow::bail!("Private windows in Safari are only supported on macOS")
+                }
+                None => anyhow::bail!(
+                    "Don't know how to open a private window in '{}' (set `family` for it in [browsers])",
+                    self.name
+                ),
+            };
+            args.push(flag.to_string());
+        } else if opts.new_window || opts.new_tab {
+            // Chromium opens URLs in a new tab of the last window by default.
+            let flag = match (self.family, opts.new_window) {
+                (Some(Family::Chromium), true) => Some("--new-window"),
+                (Some(Family::Chromium), false) => None,
+                (Some(Family::Firefox), true) => Some("--new-window"),
+                (Some(Family::Firefox), false) => Some("--new-tab"),
+                (Some(Family::Safari), _) => {
+                    anyhow::bail!("Window and tab control for Safari is only supported on macOS")
+                }
+                (None, _) => anyhow::bail!(
+                    "Don't know how to control windows in '{}' (set `family` for it in [browsers])",
+                    self.name
+                ),
+            };
+            args.extend(flag.map(str::to_string));
+        }
+        if opts.app {
+            self.require_chromium("App mode")?;
+            args.push(format!("--app={}", url));
+        } else {
+            args.push(url.to_string());
+        }
+        args.extend(opts.extra_args.iter().cloned());
+        Ok(args)
+    }
+}
+
+pub fn build_command(
+    config: &Config,
+    url: &str,
+    browser: &BrowserChoice,
+    opts: &OpenOptions,
+) -> Result<Command> {
+    let platform = Platform::detect();
+    if let BrowserChoice::Text(name) = browser {
+        if opts.needs_browser() {
+            anyhow::bail!(
+                "Private windows, window/tab control, profiles and app modes are not \
+                 supported by text browsers"
+            );
+        }
+        let mut cmd = text_browser_command(config, name.as_deref(), url)?;
+        cmd.args(&opts.extra_args);
+        return Ok(cmd);
+    }
+    let Some(launcher) = Launcher::for_choice(config, browser)? else {
+        if opts.needs_browser() {
+            anyhow::bail!(
+                "Private windows, window/tab control, profiles, app modes and browser \
+                 arguments need a specific browser; pass --browser or set default_browser"
+            );
+        }
+        return Ok(match platform {
+            Platform::MacOs => {
+                let mut cmd = macos_open_command(opts);
+                cmd.arg(url);
+                cmd
+            }
+            Platform::Linux | Platform::Wsl if opts.wait => anyhow::bail!(
+                "--wait needs a specific browser on Linux; pass --browser or set default_browser"
+            ),
+            Platform::Wsl => wsl_default_command(url),
+            Platform::Linux => {
+                let mut cmd = Command::new("xdg-open");
+                cmd.arg(url);
+                cmd
+            }
+        });
+    };
+
+    match platform {
+        Platform::MacOs => {
+            let app = launcher.macos_app.as_deref().ok_or_else(|| {
+                anyhow::anyhow!("Browser '{}' has no macos_app configured", launcher.name)
+            })?;
+            let scripted = opts.private || opts.new_window || opts.new_tab;
+            if launcher.family == Some(Family::Safari) && scripted {
+                if opts.profile.is_some() || opts.new_instance || opts.app || opts.kiosk {
+                    anyhow::bail!(
+                        "Profiles, new instances and app modes are not supported by Safari"
+                    );
+                }
+                if opts.wait || !opts.extra_args.is_empty() {
+                    anyhow::bail!(
+                        "--wait and browser arguments can't be combined with Safari window control"
+                    );
+                }
+                return Ok(safari_script_command(url, opts));
+            }
+            let args = launcher.args(url, opts)?;
+            let mut cmd = macos_open_command(opts);
+            if args == [url] {
+                cmd.args(["-a", app, url]);
+            } else {
+                // Flags only reach the browser through a fresh `open -n ... --args`.
+                cmd.args(["-na", app, "--args"]).args(&args);
+            }
+            Ok(cmd)
+        }
+        Platform::Linux | Platform::Wsl => {
+            let parts = launcher.linux_cmd.as_deref().ok_or_else(|| {
+                anyhow::anyhow!("Browser '{}' is not available on Linux", launcher.name)
+            })?;
+            let mut cmd = Command::new(&parts[0]);
+            cmd.args(&parts[1..]).args(launcher.args(url, opts)?);
+            Ok(cmd)
+        }
+    }
+}
+
+/// `open` with the flags shared by every macOS launch.
+fn macos_open_command(opts: &OpenOptions) -> Command {
+    let mut cmd = Command::new("open");
+    if opts.background {
+        cmd.arg("-g");
+    }
+    if opts.wait {
+        cmd.arg("-W");
+    }
+    cmd
+}
+
+/// Safari has no command-line switches for private browsing or window placement, so
+/// drive it through AppleScript instead. The URL is passed as an argument to the script.
+fn safari_script_command(url: &str, opts: &OpenOptions) -> Command {
+    let body: &[&str] = if opts.private {
+        &[
+            "tell application \"Safari\" to activate",
+            "tell application \"System Events\" to keystroke \"n\" using {command down, shift down}",
+            "delay 0.5",
+            "tell application \"Safari\" to set URL of front document to item 1 of argv",
+        ]
+    } else if opts.new_window {
+        &[
+            "tell application \"Safari\"",
+            "activate",
+            "make new document with properties {URL:item 1 of argv}",
+            "end tell",
+        ]
+    } else {
+        &[
+            "tell application \"Safari\"",
+            "activate",
+            "if (count of windows) is 0 then",
+            "make new document with properties {URL:item 1 of argv}",
+            "else",
+            "tell front window to set current tab to (make new tab with properties {URL:item 1 of argv})",
+            "end if",
+            "end tell",
+        ]
+    };
+    let mut cmd = Command::new("osascript");
+    cmd.args(["-e", "on run argv"]);
+    // The private window shortcut needs Safari in front, so only skip activation otherwise.
+    let skip_activate = opts.background && !opts.private;
+    for line in body {
+        if skip_activate && *line == "activate" {
+            continue;
+        }
+        cmd.args(["-e", line]);
+    }
+    cmd.args(["-e", "end run"]).arg(url);
+    cmd
+}
+
+/// Open in the Windows host's default browser. `wslview` (from wslu) is preferred
+/// when installed; otherwise go through PowerShell, which every WSL setup can reach.
+fn wsl_default_command(url: &str) -> Command {
+    if find_in_path("wslview").is_some() {
+        let mut cmd = Command::new("wslview");
+        cmd.arg(url);
+        return cmd;
+    }
+    let mut cmd = Command::new("powershell.exe");
+    cmd.args([
+        "-NoProfile",
+        "-NonInteractive",
+        "-Command",
+        &format!("Start-Process '{}'", url.replace('\'', "''")),
+    ]);
+    cmd
+}
+
+/// Commands from the `$BROWSER` environment variable, in order of preference.
+///
+/// Follows the usual convention: entries are separated by `:`, and `%s` in an entry is
+/// replaced by the URL (`%%` is a literal `%`). Entries without `%s` get the URL appended.
+fn env_browser_commands(url: &str) -> Vec<Command> {
+    let Ok(value) = std::env::var("BROWSER") else {
+        return vec![];
+    };
+    value
+        .split(':')
+        .filter_map(|entry| {
+            let mut parts = entry.split_whitespace();
+            let program = parts.next()?;
+            let mut substituted = false;
+            let mut args: Vec<String> = parts
+                .map(|arg| {
+                    let (arg, has_url) = substitute_url(arg, url);
+                    substituted |= has_url;
+                    arg
+                })
+                .collect();
+            if !substituted {
+                args.push(url.to_string());
+            }
+            let mut cmd = Command::new(program);
+            cmd.args(args);
+            Some(cmd)
+        })
+        .collect()
+}
+
+/// `arg` of a `$BROWSER` entry with `%s` replaced by `url` and `%%` by `%`, in one pass so
+/// that neither replacement sees the other's output; and whether `%s` occurred.
+fn substitute_url(arg: &str, url: &str) -> (String, bool) {
+    let mut out = String::new();
+    let mut substituted = false;
+    let mut chars = arg.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
         }
-        BrowserChoice::Brave => {
-            cmd.args(["-a", "Brave Browser", url]);
+        match chars.clone().next() {
+            Some('s') => {
+                chars.next();
+                out.push_str(url);
+                substituted = true;
+            }
+            Some('%') => {
+                chars.next();
+                out.push('%');
+            }
+            _ => out.push('%'),
         }
     }
-    let status = cmd.status()?;
+    (out, substituted)
+}
+
+/// Start a browser without waiting for it. Browser binaries keep running for as long
+/// as the browser is open, so their output is discarded instead of cluttering the terminal.
+fn spawn_detached(mut cmd: Command) -> Result<()> {
+    trace!("Starting {}", format_command(&cmd));
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", program))?;
+    Ok(())
+}
+
+fn run(mut cmd: Command) -> Result<()> {
+    trace!("Running {}", format_command(&cmd));
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    let status = cmd
+        .status()
+        .with_context(|| format!("Failed to run {}", program))?;
     if !status.success() {
-        anyhow::bail!("open exited with {:?}", status.code());
+        anyhow::bail!("{} exited with {:?}", program, status.code());
+    }
+    Ok(())
+}
+
+/// Schemes that can run code or read local data when opened from an untrusted config.
+const BLOCKED_SCHEMES: &[&str] = &["javascript", "vbscript", "data"];
+
+/// Refuse URLs with a dangerous scheme: `javascript:`, `vbscript:`, `data:`, and `file:`
+/// unless the config sets `allow_file_urls = true`.
+pub fn check_scheme(config: &Config, url: &str) -> Result<()> {
+    if !crate::template::has_scheme(url) {
+        return Ok(());
+    }
+    let scheme = url.split_once(':').map(|(s, _)| s.to_ascii_lowercase());
+    match scheme.as_deref() {
+        Some(s) if BLOCKED_SCHEMES.contains(&s) => anyhow::bail!(
+            "Refusing to open a '{}:' URL, which can run code; pass --force if you trust it",
+            s
+        ),
+        Some("file") if !config.allow_file_urls => anyhow::bail!(
+            "Refusing to open a 'file:' URL; set `allow_file_urls = true` in the config or pass --force"
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Schemes opened in a browser; any other scheme is an app link.
+const WEB_SCHEMES: &[&str] = &["http", "https", "file", "about", "data", "javascript"];
+
+/// App link schemes allowed when the config has no `allowed_schemes`.
+const DEFAULT_ALLOWED_SCHEMES: &[&str] =
+    &["mailto", "tel", "slack", "vscode", "zoommtg", "msteams"];
+
+/// The scheme of `url` if it is an app link (`slack://`, `mailto:`, ...) rather than a web page.
+fn app_link_scheme(url: &str) -> Option<String> {
+    if !crate::template::has_scheme(url) {
+        return None;
+    }
+    let scheme = url.split_once(':')?.0.to_ascii_lowercase();
+    (!WEB_SCHEMES.contains(&scheme.as_str())).then_some(scheme)
+}
+
+/// Hand an app link to the OS, which opens the app registered for its scheme, as long as
+/// the scheme is in the config's `allowed_schemes`.
+fn open_app_link(config: &Config, url: &str, scheme: &str, dry_run: bool) -> Result<()> {
+    let allowed = match &config.allowed_schemes {
+        Some(schemes) => schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme)),
+        None => DEFAULT_ALLOWED_SCHEMES.contains(&scheme),
+    };
+    if !allowed {
+        anyhow::bail!(
+            "'{}:' links are not allowed; add \"{}\" to `allowed_schemes` in the config to open them",
+            scheme,
+            scheme
+        );
+    }
+    trace!(
+        "'{}:' is an app link; opening it with the app registered for it",
+        scheme
+    );
+    if is_headless() {
+        print_url(url);
+        return Ok(());
+    }
+    let cmd = build_command(
+        config,
+        url,
+        &BrowserChoice::Default,
+        &OpenOptions::default(),
+    )?;
+    if dry_run {
+        print_dry_run(&BrowserChoice::Default, &cmd);
+        return Ok(());
+    }
+    run(cmd)
+}
+
+pub fn open_url(
+    config: &Config,
+    url: &str,
+    browser: &BrowserChoice,
+    opts: &OpenOptions,
+) -> Result<()> {
+    if !opts.force {
+        check_scheme(config, url)?;
+    }
+    if let Some(scheme) = app_link_scheme(url) {
+        // Browser choice and options don't apply to links that open another app.
+        return open_app_link(config, url, &scheme, opts.dry_run);
+    }
+    if let Some(port) = opts.cdp_port {
+        if opts.dry_run {
+            println!("Would open {} through the DevTools port {}", url, port);
+            return Ok(());
+        }
+        trace!("Opening through the DevTools port {}", port);
+        return open_with_cdp(url, port, opts);
+    }
+    if *browser == BrowserChoice::Default && !opts.needs_browser() {
+        // $BROWSER entries are tried in turn; if none works, use the system opener.
+        let commands = env_browser_commands(url);
+        if !commands.is_empty() {
+            trace!("Trying the commands in $BROWSER before the system opener");
+        }
+        for cmd in commands {
+            if opts.dry_run {
+                // Only the first entry runs unless it fails.
+                print_dry_run(browser, &cmd);
+                return Ok(());
+            }
+            // Text browsers need the terminal until they exit; others are started like
+            // the browsers launched directly below, so an entry that starts counts.
+            let program = cmd.get_program().to_string_lossy().into_owned();
+            let in_terminal = TEXT_BROWSERS.contains(&program.rsplit('/').next().unwrap_or(""));
+            let result = if in_terminal || opts.wait {
+                run(cmd)
+            } else {
+                spawn_detached(cmd)
+            };
+            if result.is_ok() {
+                return Ok(());
+            }
+        }
+    }
+    // With --dry-run, the command is shown even where it couldn't open a window.
+    if !opts.dry_run && !matches!(browser, BrowserChoice::Text(_)) && is_headless() {
+        // A GUI browser would open on a screen nobody is looking at (or not at all).
+        trace!("No display (SSH session or no X11/Wayland); printing the URL instead");
+        print_url(url);
+        return Ok(());
+    }
+    if opts.activate_existing && !opts.dry_run && activate_existing_tab(config, url, browser)? {
+        return Ok(());
+    }
+    let cmd = build_command(config, url, browser, opts)?;
+    if opts.dry_run {
+        print_dry_run(browser, &cmd);
+        return Ok(());
+    }
+    // Launchers (`open`, `xdg-open`, ...) return right away; browser binaries started
+    // directly on Linux don't, so only wait for those when asked to.
+    let launched_directly =
+        matches!(browser, BrowserChoice::Named(_)) && Platform::detect() != Platform::MacOs;
+    let result = if launched_directly && !opts.wait {
+        spawn_detached(cmd)
+    } else {
+        run(cmd)
+    };
+    result.map_err(|e| match missing_browser_error(config, browser) {
+        Some(missing) => missing,
+        None => e,
+    })
+}
+
+/// When a named browser failed because it isn't installed, an error saying so that
+/// lists the installed browsers and suggests the closest one.
+fn missing_browser_error(config: &Config, browser: &BrowserChoice) -> Option<anyhow::Error> {
+    let platform = Platform::detect();
+    let launcher = Launcher::for_choice(config, browser).ok()??;
+    if launcher.is_installed(platform) {
+        return None;
+    }
+    let missing = match platform {
+        Platform::MacOs => format!("application '{}'", launcher.macos_app.as_deref()?),
+        Platform::Linux | Platform::Wsl => format!("`{}`", launcher.linux_cmd.as_ref()?[0]),
+    };
+    let mut message = format!(
+        "Browser '{}' is not installed ({} not found)",
+        launcher.name, missing
+    );
+    let installed: Vec<Launcher> = available_browsers(config)
+        .ok()?
+        .into_iter()
+        .filter(|b| b.installed)
+        .filter_map(|b| Launcher::for_choice(config, &BrowserChoice::Named(b.name)).ok()?)
+        .collect();
+    if installed.is_empty() {
+        message.push_str("\nNo supported browsers were found; see `web browsers --all`.");
+        return Some(anyhow::anyhow!(message));
+    }
+    let names: Vec<&str> = installed.iter().map(|l| l.name.as_str()).collect();
+    message.push_str(&format!("\nInstalled browsers: {}", names.join(", ")));
+    // The same engine understands the same flags, so prefer it as the alternative.
+    let suggestion = installed
+        .iter()
+        .find(|l| l.family.is_some() && l.family == launcher.family)
+        .unwrap_or(&installed[0]);
+    message.push_str(&format!("\nTry `--browser {}` instead.", suggestion.name));
+    Some(anyhow::anyhow!(message))
+}
+
+/// Open (or focus) the URL in an already running browser over the DevTools protocol,
+/// printing the id of the tab so scripts can refer to it.
+fn open_with_cdp(url: &str, port: u16, opts: &OpenOptions) -> Result<()> {
+    let unsupported = opts.private
+        || opts.new_window
+        || opts.profile.is_some()
+        || opts.new_instance
+        || opts.app
+        || opts.kiosk
+        || !opts.extra_args.is_empty();
+    if unsupported {
+        anyhow::bail!(
+            "--cdp opens tabs in the running browser; window, profile and app options \
+             and browser arguments don't apply"
+        );
     }
+    let existing = if opts.activate_existing {
+        cdp::activate_tab(port, url)?
+    } else {
+        None
+    };
+    let id = match existing {
+        Some(id) => id,
+        None => cdp::open_tab(port, url)?,
+    };
+    println!("{}", id);
     Ok(())
 }
+
+/// Focus a tab already showing `url`. Returns whether one was found.
+fn activate_existing_tab(config: &Config, url: &str, browser: &BrowserChoice) -> Result<bool> {
+    if Platform::detect() != Platform::MacOs {
+        anyhow::bail!("--activate-existing is only supported on macOS");
+    }
+    let launcher = Launcher::for_choice(config, browser)?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "--activate-existing needs a specific browser; pass --browser or set default_browser"
+        )
+    })?;
+    let app = launcher.macos_app.as_deref().ok_or_else(|| {
+        anyhow::anyhow!("Browser '{}' has no macos_app configured", launcher.name)
+    })?;
+    match launcher.family {
+        Some(family @ (Family::Safari | Family::Chromium)) => {
+            macos::activate_tab(app, family, url)
+        }
+        _ => anyhow::bail!(
+            "--activate-existing needs a browser that supports AppleScript (Safari or a Chromium-based one), not '{}'",
+            launcher.name
+        ),
+    }
+}
+
+/// Whether there is no local display to open a GUI browser on: an SSH session, or
+/// Linux without X11/Wayland. WSL hands URLs to Windows, so it always has one.
+fn is_headless() -> bool {
+    let over_ssh = ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some());
+    if over_ssh {
+        return true;
+    }
+    match Platform::detect() {
+        Platform::Linux => ["DISPLAY", "WAYLAND_DISPLAY"]
+            .iter()
+            .all(|var| std::env::var_os(var).is_none_or(|v| v.is_empty())),
+        Platform::MacOs | Platform::Wsl => false,
+    }
+}
+
+/// Print the URL instead of opening it, as an OSC 8 hyperlink when writing to a
+/// terminal so it can be clicked.
+fn print_url(url: &str) {
+    if std::io::stdout().is_terminal() {
+        println!("\x1b]8;;{url}\x1b\\{url}\x1b]8;;\x1b\\");
+    } else {
+        println!("{url}");
+    }
+}
+
+/// Show the command `--dry-run` would have run, and for which browser.
+fn print_dry_run(browser: &BrowserChoice, cmd: &Command) {
+    println!("[{}] {}", browser, format_command(cmd));
+}
+
+/// Stand-in for the URL when showing commands without a real URL.
+pub const URL_PLACEHOLDER: &str = "<url>";
+
+/// Render a command the way it would be typed in a shell.
+pub fn format_command(cmd: &Command) -> String {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|part| {
+            let part = part.to_string_lossy();
+            let plain = !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,<>".contains(c));
+            if plain {
+                part.into_owned()
+            } else {
+                format!("'{}'", part.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The program that opens URLs in the system default browser, and whether it is installed.
+pub fn default_opener(config: &Config) -> Result<(String, bool)> {
+    let cmd = build_command(
+        config,
+        URL_PLACEHOLDER,
+        &BrowserChoice::Default,
+        &OpenOptions::default(),
+    )?;
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    let found = find_in_path(&program).is_some();
+    Ok((program, found))
+}
+
+/// A browser selectable with `--browser`, as reported by `web browsers`.
+pub struct AvailableBrowser {
+    pub name: String,
+    pub installed: bool,
+    /// Command that would open a URL, or why the browser can't be used here
+    pub command: Result<String, String>,
+}
+
+/// Built-in browsers followed by the ones defined in the config.
+pub fn available_browsers(config: &Config) -> Result<Vec<AvailableBrowser>> {
+    let platform = Platform::detect();
+    let mut names: Vec<String> = BUILTIN_BROWSERS
+        .iter()
+        .map(|b| b.name.to_string())
+        .collect();
+    for name in config.browsers.keys() {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    names
+        .into_iter()
+        .map(|name| {
+            let choice = BrowserChoice::Named(name.clone());
+            let launcher = Launcher::for_choice(config, &choice)?;
+            let installed = launcher.is_some_and(|l| l.is_installed(platform));
+            let command = build_command(config, URL_PLACEHOLDER, &choice, &OpenOptions::default())
+                .map(|cmd| format_command(&cmd))
+                .map_err(|e| format!("{e:#}"));
+            Ok(AvailableBrowser {
+                name,
+                installed,
+                command,
+            })
+        })
+        .collect()
+}
+
+/// Value completer for `--browser`: installed browsers first, then the other built-in
+/// and configured ones.
+pub fn complete_browser(
+    current: &std::ffi::OsStr,
+) -> Vec<clap_complete::engine::CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return vec![];
+    };
+    let Ok(mut browsers) = config::load().and_then(|config| available_browsers(&config)) else {
+        return vec![];
+    };
+    browsers.sort_by_key(|b| !b.installed);
+    let default = ("default".to_string(), "system default browser");
+    std::iter::once(default)
+        .chain(browsers.into_iter().map(|b| {
+            let help = if b.installed {
+                "installed"
+            } else {
+                "not installed"
+            };
+            (b.name, help)
+        }))
+        .filter(|(name, _)| name.starts_with(current))
+        .map(|(name, help)| {
+            clap_complete::engine::CompletionCandidate::new(name).help(Some(help.into()))
+        })
+        .collect()
+}
+
+/// AppleScript integration. Only called on macOS, but kept platform-independent so it
+/// is compiled and checked everywhere.
+mod macos {
+    use anyhow::{Context, Result};
+    use std::process::Command;
+
+    use super::Family;
+
+    /// Scripting terms are resolved at compile time, so the script is compiled against
+    /// the dictionary of a reference app and then told to talk to `app`.
+    fn tab_script(family: Family) -> String {
+        let (terms, select_tab) = match family {
+            Family::Safari => ("Safari", "set current tab of w to t"),
+            _ => ("Google Chrome", "set active tab index of w to i"),
+        };
+        format!(
+            r#"on run argv
+    set appName to item 1 of argv
+    set target to item 2 of argv
+    if application appName is not running then return "missing"
+    using terms from application "{terms}"
+        tell application appName
+            repeat with w in windows
+                set i to 0
+                repeat with t in tabs of w
+                    set i to i + 1
+                    set u to URL of t
+                    if u is target or u is (target & "/") then
+                        {select_tab}
+                        set index of w to 1
+                        activate
+                        return "found"
+                    end if
+                end repeat
+            end repeat
+        end tell
+    end using terms from
+    return "missing"
+end run"#
+        )
+    }
+
+    /// Bring the first tab of `app` showing `url` to the front. Returns whether one was found.
+    pub fn activate_tab(app: &str, family: Family, url: &str) -> Result<bool> {
+        let output = Command::new("osascript")
+            .args(["-e", &tab_script(family), app, url])
+            .output()
+            .context("Failed to run osascript")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "AppleScript failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim() == "found")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitute_url_replaces_in_one_pass() {
+        let url = "https://example.com/a%%b";
+        assert_eq!(substitute_url("%s", url), (url.to_string(), true));
+        assert_eq!(
+            substitute_url("--url=%s", "https://x"),
+            ("--url=https://x".to_string(), true)
+        );
+        assert_eq!(substitute_url("%%s", url), ("%s".to_string(), false));
+        assert_eq!(substitute_url("100%", url), ("100%".to_string(), false));
+    }
+
+    #[test]
+    fn env_browser_commands_splits_entries() {
+        // The only test reading $BROWSER, so setting it can't race another.
+        std::env::set_var("BROWSER", "firefox --new-tab=%s:lynx");
+        let commands: Vec<(String, Vec<String>)> = env_browser_commands("https://x")
+            .iter()
+            .map(|cmd| {
+                let args = cmd.get_args().map(|a| a.to_string_lossy().into_owned());
+                (
+                    cmd.get_program().to_string_lossy().into_owned(),
+                    args.collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            commands,
+            [
+                (
+                    "firefox".to_string(),
+                    vec!["--new-tab=https://x".to_string()]
+                ),
+                ("lynx".to_string(), vec!["https://x".to_string()]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Minimal client for the HTTP endpoints of the Chrome DevTools Protocol, available
+//! when a Chromium-based browser runs with `--remote-debugging-port`. These can list, open
+//! and focus tabs but not choose the window a tab opens in, which takes the websocket
+//! protocol and is not supported.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use crate::template::percent_encode;
+
+/// A tab (or other target) as listed by `/json/list`.
+#[derive(Debug, Deserialize)]
+pub struct Target {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub url: String,
+}
+
+/// Send a request to the debugging endpoint and return the response body.
+fn request(port: u16, method: &str, path: &str) -> Result<String> {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).with_context(|| {
+        format!(
+            "Could not connect to the DevTools port {} (start the browser with --remote-debugging-port={})",
+            port, port
+        )
+    })?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: localhost:{port}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+    )?;
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .context("Failed to read the DevTools response")?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| anyhow::anyhow!("Malformed DevTools response"))?;
+    let status = head.lines().next().unwrap_or_default();
+    if !status.contains(" 200 ") {
+        anyhow::bail!("DevTools request {} {} failed: {}", method, path, status);
+    }
+    Ok(body.to_string())
+}
+
+pub fn list_tabs(port: u16) -> Result<Vec<Target>> {
+    let body = request(port, "GET", "/json/list")?;
+    let targets: Vec<Target> =
+        serde_json::from_str(&body).context("Failed to parse the DevTools tab list")?;
+    Ok(targets.into_iter().filter(|t| t.kind == "page").collect())
+}
+
+/// Open `url` in a new tab and return the tab's id.
+pub fn open_tab(port: u16, url: &str) -> Result<String> {
+    let body = request(port, "PUT", &format!("/json/new?{}", percent_encode(url)))?;
+    let target: Target =
+        serde_json::from_str(&body).context("Failed to parse the DevTools response")?;
+    Ok(target.id)
+}
+
+/// Focus the tab showing `url`, if any. Returns its id.
+pub fn activate_tab(port: u16, url: &str) -> Result<Option<String>> {
+    let trimmed = url.trim_end_matches('/');
+    let Some(tab) = list_tabs(port)?
+        .into_iter()
+        .find(|t| t.url.trim_end_matches('/') == trimmed)
+    else {
+        return Ok(None);
+    };
+    request(port, "GET", &format!("/json/activate/{}", tab.id))?;
+    Ok(Some(tab.id))
+}
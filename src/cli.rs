@@ -1,7 +1,10 @@
-use clap::{Parser, Subcommand, ValueHint};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum, ValueHint};
 use clap_complete::engine::ArgValueCompleter;
+use std::time::Duration;
 
-use crate::config::complete_alias;
+use crate::browser::{builtin_browser, complete_browser, OpenOptions};
+use crate::config::{complete_alias, complete_group, complete_tag, complete_trash, Alias};
+use crate::session::complete_session;
 
 #[derive(Debug, Parser)]
 #[command(name = "web", version, about = "Open URL aliases in a browser")]
@@ -14,70 +17,750 @@ pub struct Cli {
     pub firefox: bool,
     #[arg(long, group = "browser_choice")]
     pub brave: bool,
+    #[arg(long, group = "browser_choice")]
+    pub edge: bool,
+    #[arg(long, group = "browser_choice")]
+    pub arc: bool,
+    #[arg(long, group = "browser_choice")]
+    pub vivaldi: bool,
+    #[arg(long, group = "browser_choice")]
+    pub opera: bool,
+    #[arg(long, group = "browser_choice")]
+    pub librewolf: bool,
+    #[arg(long, group = "browser_choice")]
+    pub chromium: bool,
+    #[arg(long, group = "browser_choice")]
+    pub zen: bool,
+    /// Browser to open the URL in (e.g. safari, chrome, firefox)
+    #[arg(
+        long,
+        value_name = "NAME",
+        group = "browser_choice",
+        add = ArgValueCompleter::new(complete_browser)
+    )]
+    pub browser: Option<BrowserChoice>,
+    /// Open in a text browser inside this terminal (w3m, lynx, browsh, ...)
+    #[arg(
+        long,
+        value_name = "NAME",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "",
+        group = "browser_choice"
+    )]
+    pub tui_browser: Option<String>,
+    /// Open in every installed browser at once, e.g. to compare rendering
+    #[arg(long, group = "browser_choice")]
+    pub all_browsers: bool,
+    /// Open in a private/incognito window
+    #[arg(long, visible_alias = "private")]
+    pub incognito: bool,
+    /// Open in a new browser window
+    #[arg(long, conflicts_with = "new_tab")]
+    pub new_window: bool,
+    /// Open in a new tab of the current window
+    #[arg(long)]
+    pub new_tab: bool,
+    /// Browser profile to open in (Chromium profile directory or Firefox profile name)
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+    /// Start a separate browser process, e.g. to run another profile alongside (Firefox)
+    #[arg(long)]
+    pub new_instance: bool,
+    /// Open as a chromeless app window (Chromium-based browsers)
+    #[arg(long)]
+    pub app: bool,
+    /// Open fullscreen in kiosk mode (Chromium-based browsers)
+    #[arg(long)]
+    pub kiosk: bool,
+    /// Open without bringing the browser to the foreground (macOS)
+    #[arg(long)]
+    pub background: bool,
+    /// Wait until the browser exits before returning
+    #[arg(long)]
+    pub wait: bool,
+    /// Focus a tab already showing the URL instead of opening a new one (macOS, or with --cdp)
+    #[arg(long)]
+    pub activate_existing: bool,
+    /// Open in a running Chromium-based browser via its DevTools port and print the tab id
+    #[arg(
+        long,
+        value_name = "PORT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "9222"
+    )]
+    pub cdp: Option<u16>,
+    /// Pause between pages when opening several (e.g. 500ms, 2s)
+    #[arg(long, value_name = "DURATION", value_parser = parse_delay)]
+    pub delay: Option<Duration>,
+    /// Print the command that would open each URL, and the browser it runs, without running it
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Never prompt; fail when a template argument is missing
+    #[arg(long)]
+    pub no_input: bool,
+    /// Allow URL schemes that are blocked as dangerous (javascript:, data:, file:), and let
+    /// `add` overwrite existing aliases without asking
+    #[arg(long, global = true)]
+    pub force: bool,
+    /// Add or replace a query parameter of the URL (repeatable), e.g. --param from=now-6h
+    #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_param)]
+    pub params: Vec<(String, String)>,
+    /// Locale whose URL variant to open (e.g. ja); defaults to $LC_ALL, $LC_MESSAGES or $LANG
+    #[arg(long, value_name = "LOCALE")]
+    pub locale: Option<String>,
+    /// Open the page at this #fragment (anchor), replacing any in the URL
+    #[arg(long, value_name = "ID")]
+    pub fragment: Option<String>,
+    /// Don't ask for confirmation before removing aliases, opening many at once or opening
+    /// one marked `confirm`
+    #[arg(short, long, global = true)]
+    pub yes: bool,
+    /// Explain each step: the config loaded, the alias or rule matched, the browser chosen
+    /// and why, and the command run
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+    /// Don't print informational messages (errors still go to stderr); `add` doesn't prompt
+    /// either, so replacing an existing alias then needs --force
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+    /// Don't color output (also off when NO_COLOR is set or stdout is not a terminal)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+    /// Print long listings (`list`, `search`) directly instead of through $PAGER
+    #[arg(long, global = true)]
+    pub no_pager: bool,
+    /// Print the resolved URL instead of opening it
+    #[arg(long)]
+    pub print: bool,
+    /// What to do with the resolved URL instead of opening it (set by `web copy` and `web qr`)
+    #[arg(skip)]
+    pub action: UrlAction,
 
     #[command(subcommand)]
     pub command: Option<Commands>,
 
-    /// Alias to open (when no subcommand given)
-    #[arg(value_hint = ValueHint::Other, add = ArgValueCompleter::new(complete_alias))]
-    pub alias: Option<String>,
+    /// Aliases to open, in order (when no subcommand given)
+    #[arg(value_name = "ALIAS", value_hint = ValueHint::Other, add = ArgValueCompleter::new(complete_alias))]
+    pub alias: Vec<String>,
+
+    /// Arguments after `--` are passed to the browser
+    #[arg(last = true, value_name = "BROWSER_ARGS")]
+    pub browser_args: Vec<String>,
 }
 
 impl Cli {
-    pub fn browser_choice(&self) -> BrowserChoice {
-        if self.safari {
-            BrowserChoice::Safari
-        } else if self.chrome {
-            BrowserChoice::Chrome
-        } else if self.firefox {
-            BrowserChoice::Firefox
-        } else if self.brave {
-            BrowserChoice::Brave
-        } else {
-            BrowserChoice::Default
+    /// Options for opening `entry`. Its stored profile only applies when the browser
+    /// was not overridden on the command line, since profiles belong to a browser.
+    pub fn open_options(&self, entry: &Alias) -> OpenOptions {
+        let profile = match &self.profile {
+            Some(profile) => Some(profile.clone()),
+            None if self.browser_flag().is_none() => entry.profile.clone(),
+            None => None,
+        };
+        OpenOptions {
+            private: self.incognito,
+            new_window: self.new_window,
+            new_tab: self.new_tab,
+            profile,
+            new_instance: self.new_instance,
+            app: self.app || entry.app,
+            kiosk: self.kiosk,
+            background: self.background,
+            wait: self.wait,
+            extra_args: self.browser_args.clone(),
+            activate_existing: self.activate_existing,
+            cdp_port: self.cdp,
+            force: self.force,
+            dry_run: self.dry_run,
         }
     }
+
+    /// Whether `name` is a subcommand or one of its aliases, which `web <name>` runs instead
+    /// of opening an alias of that name.
+    pub fn is_subcommand(name: &str) -> bool {
+        name == "help"
+            || Cli::command()
+                .get_subcommands()
+                .any(|c| c.get_name() == name || c.get_all_aliases().any(|a| a == name))
+    }
+
+    /// Whether the resolved URL is printed, copied or shown instead of opened.
+    pub fn skips_browser(&self) -> bool {
+        self.print || self.action != UrlAction::Open
+    }
+
+    /// Browser selected by flags, if any.
+    pub fn browser_flag(&self) -> Option<BrowserChoice> {
+        if let Some(browser) = &self.browser {
+            return Some(browser.clone());
+        }
+        if let Some(name) = &self.tui_browser {
+            return Some(BrowserChoice::Text(
+                Some(name.clone()).filter(|n| !n.is_empty()),
+            ));
+        }
+        let flags = [
+            (self.safari, "safari"),
+            (self.chrome, "chrome"),
+            (self.firefox, "firefox"),
+            (self.brave, "brave"),
+            (self.edge, "edge"),
+            (self.arc, "arc"),
+            (self.vivaldi, "vivaldi"),
+            (self.opera, "opera"),
+            (self.librewolf, "librewolf"),
+            (self.chromium, "chromium"),
+            (self.zen, "zen"),
+        ];
+        flags
+            .into_iter()
+            .find(|(set, _)| *set)
+            .map(|(_, name)| BrowserChoice::Named(name.to_string()))
+    }
+
+    /// Browser selected by flags, falling back to `default_browser` from the config.
+    pub fn browser_choice(&self, config_default: Option<&str>) -> BrowserChoice {
+        self.browser_flag().unwrap_or_else(|| {
+            config_default
+                .and_then(|name| name.parse().ok())
+                .unwrap_or(BrowserChoice::Default)
+        })
+    }
+}
+
+/// Parse a delay such as `500ms`, `2s` or `1.5s`; a bare number is in milliseconds.
+fn parse_delay(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let (number, scale) = if let Some(ms) = s.strip_suffix("ms") {
+        (ms, 0.001)
+    } else if let Some(secs) = s.strip_suffix('s') {
+        (secs, 1.0)
+    } else {
+        (s, 0.001)
+    };
+    let value: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid duration '{}' (expected e.g. 500ms or 2s)", s))?;
+    Duration::try_from_secs_f64(value * scale).map_err(|e| e.to_string())
+}
+
+fn parse_param(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{}'", s)),
+    }
+}
+
+/// Output of `web list --format`. The columns are alias, url, tags, description and
+/// pinned; columns added later go at the end.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ListFormat {
+    /// An array of objects
+    Json,
+    /// Tab-separated, with a header row
+    Tsv,
+    /// Comma-separated, with a header row
+    Csv,
+    /// `alias url [tags...]` lines, as read by `web add --stdin`; aliases with several URLs
+    /// are left out
+    Plain,
+}
+
+/// What `web list --group-by` clusters aliases by.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum GroupBy {
+    /// The host of the URL, without `www.`
+    Domain,
 }
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     /// Register new alias(es) — comma-separated for multiple (e.g. claude,c)
     Add {
-        #[arg(value_hint = ValueHint::Other)]
-        aliases: String,
-        #[arg(value_hint = ValueHint::Url)]
-        url: String,
+        #[arg(value_hint = ValueHint::Other, required_unless_present_any = ["stdin", "edit"])]
+        aliases: Option<String>,
+        #[arg(
+            value_hint = ValueHint::Url,
+            required_unless_present_any = ["clipboard", "stdin", "edit"]
+        )]
+        url: Option<String>,
+        /// Take the URL from the clipboard, after showing it for confirmation
+        #[arg(long, conflicts_with = "url")]
+        clipboard: bool,
+        /// Read `alias url [tags...]` lines from stdin, or bare URLs to name after the page title
+        #[arg(long, conflicts_with_all = ["aliases", "url", "clipboard", "check"])]
+        stdin: bool,
+        /// Write the alias, URL, tags and description in $EDITOR, starting from those given
+        #[arg(long, conflicts_with_all = ["stdin", "clipboard"])]
+        edit: bool,
+        /// Browser to always open these alias(es) in
+        #[arg(long, value_name = "NAME", add = ArgValueCompleter::new(complete_browser))]
+        browser: Option<String>,
+        /// Browser profile to always open these alias(es) in
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+        /// Always open these alias(es) as a chromeless app window
+        #[arg(long)]
+        app: bool,
+        /// Ask for confirmation each time these alias(es) are opened
+        #[arg(long)]
+        confirm: bool,
+        /// Store the URL without checking that it is valid
+        #[arg(long)]
+        no_verify: bool,
+        /// Request the URL, report its status and offer to store where redirects lead
+        #[arg(long)]
+        check: bool,
+        /// Resolve a short URL (bit.ly, t.co, ...) and store where it redirects to
+        #[arg(long)]
+        expand: bool,
+        /// Join extra words after the alias onto the URL path (e.g. `web gh rust-lang/rust`)
+        #[arg(long)]
+        append_path: bool,
+        /// Tag these alias(es); repeat or comma-separate for several (e.g. news,daily)
+        #[arg(long = "tag", value_name = "TAG", value_delimiter = ',', add = ArgValueCompleter::new(complete_tag))]
+        tags: Vec<String>,
+        /// Note on what the alias is for, shown by `web list --long` and in completions
+        #[arg(long, value_name = "TEXT")]
+        desc: Option<String>,
+        /// List these alias(es) first in `web list`, completions and the picker
+        #[arg(long)]
+        pin: bool,
     },
-    /// Remove alias(es) — comma-separated for multiple (e.g. claude,c)
+    /// Remove alias(es) — comma-separated for multiple (e.g. claude,c), or a glob such as 'jira-*'
     Remove {
-        #[arg(value_hint = ValueHint::Other, add = ArgValueCompleter::new(complete_alias))]
-        aliases: String,
+        #[arg(
+            value_hint = ValueHint::Other,
+            add = ArgValueCompleter::new(complete_alias),
+            required_unless_present = "all"
+        )]
+        aliases: Option<String>,
+        /// Remove every alias, after backing up the config and typing the alias count to confirm
+        #[arg(long, conflicts_with = "aliases")]
+        all: bool,
     },
     /// List all aliases
-    List,
+    List {
+        /// Only list aliases whose name or URL contains this text, or matches this glob
+        /// (e.g. 'gh-*'), ignoring case
+        #[arg(value_hint = ValueHint::Other)]
+        pattern: Option<String>,
+        /// Only list aliases carrying this tag
+        #[arg(long, value_name = "TAG", add = ArgValueCompleter::new(complete_tag))]
+        tag: Option<String>,
+        /// One alias per line, with its description
+        #[arg(short, long)]
+        long: bool,
+        /// Machine-readable output, with one row per alias in alphabetical order
+        #[arg(long, value_name = "FORMAT", conflicts_with = "long")]
+        format: Option<ListFormat>,
+        /// Cluster aliases under headers, e.g. `domain` for the host of each URL
+        #[arg(long, value_name = "KEY", conflicts_with_all = ["long", "format"])]
+        group_by: Option<GroupBy>,
+    },
+    /// List aliases as a tree grouped by namespace (`work/jira` under `work/`), with counts
+    Tree {
+        /// Only show this namespace, e.g. `work/`
+        #[arg(value_hint = ValueHint::Other, add = ArgValueCompleter::new(complete_alias))]
+        namespace: Option<String>,
+        /// Only list aliases carrying this tag
+        #[arg(long, value_name = "TAG", add = ArgValueCompleter::new(complete_tag))]
+        tag: Option<String>,
+    },
+    /// Fuzzy-find aliases by name, URL, tags and description, best matches first
+    Search {
+        /// Words to look for; every word has to match
+        #[arg(required = true, value_hint = ValueHint::Other)]
+        query: Vec<String>,
+        /// Open the best match; in a terminal, choose among the matches when there are several
+        #[arg(long)]
+        open: bool,
+        /// Show at most this many matches
+        #[arg(short = 'n', long, value_name = "N", default_value_t = 10)]
+        limit: usize,
+    },
+    /// Print the aliases whose URLs match a regex, e.g. to find those on a moved domain
+    Grep {
+        /// Regular expression searched for in alias URLs
+        #[arg(value_hint = ValueHint::Other)]
+        pattern: String,
+        /// Match regardless of case
+        #[arg(short, long)]
+        ignore_case: bool,
+    },
+    /// Find aliases pointing at the same page (ignoring trailing slashes and tracking
+    /// parameters such as utm_source) and merge each set into one alias
+    Dedupe,
+    /// Show everything known about an alias: its settings, history and where it is defined
+    Info {
+        #[arg(add = ArgValueCompleter::new(complete_alias))]
+        alias: String,
+    },
+    /// Exit with 0 if `web <alias>` would open something and 1 if not, printing nothing
+    Exists {
+        #[arg(add = ArgValueCompleter::new(complete_alias))]
+        alias: String,
+    },
+    /// Print the URL an alias resolves to without opening it, e.g. `curl $(web get api)`
+    Get {
+        /// Alias (or URL), followed by its arguments
+        #[arg(
+            value_name = "ALIAS",
+            required = true,
+            value_hint = ValueHint::Other,
+            add = ArgValueCompleter::new(complete_alias)
+        )]
+        words: Vec<String>,
+    },
+    /// Show the URL an alias resolves to as a QR code, e.g. to open it on a phone
+    Qr {
+        /// Alias (or URL), followed by its arguments
+        #[arg(
+            value_name = "ALIAS",
+            required = true,
+            value_hint = ValueHint::Other,
+            add = ArgValueCompleter::new(complete_alias)
+        )]
+        words: Vec<String>,
+        /// Write the QR code to this PNG file instead of the terminal
+        #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+        png: Option<std::path::PathBuf>,
+    },
+    /// Copy the URL an alias resolves to to the clipboard, e.g. to paste it into a chat
+    Copy {
+        /// Alias (or URL), followed by its arguments
+        #[arg(
+            value_name = "ALIAS",
+            required = true,
+            value_hint = ValueHint::Other,
+            add = ArgValueCompleter::new(complete_alias)
+        )]
+        words: Vec<String>,
+    },
+    /// Bring back an alias removed with `web remove`
+    Restore {
+        #[arg(value_hint = ValueHint::Other, add = ArgValueCompleter::new(complete_trash))]
+        alias: String,
+    },
+    /// List or empty the removed aliases kept for `web restore`
+    #[command(subcommand)]
+    Trash(TrashCommand),
+    /// Open aliases, even ones named like a subcommand (`web open list`), or every alias
+    /// carrying a tag
+    Open {
+        /// Alias (or URL), followed by its arguments
+        #[arg(
+            value_name = "ALIAS",
+            required_unless_present = "tag",
+            conflicts_with = "tag",
+            value_hint = ValueHint::Other,
+            add = ArgValueCompleter::new(complete_alias)
+        )]
+        words: Vec<String>,
+        /// Tag to open
+        #[arg(long, value_name = "TAG", add = ArgValueCompleter::new(complete_tag))]
+        tag: Option<String>,
+    },
+    /// Add, remove and list the tags of aliases
+    #[command(subcommand)]
+    Tag(TagCommand),
+    /// Manage and open groups of aliases
+    #[command(subcommand)]
+    Group(GroupCommand),
+    /// Save and reopen named sets of URLs
+    #[command(subcommand)]
+    Session(SessionCommand),
+    /// List installed browsers and the command used to open each
+    Browsers {
+        /// Also show supported browsers that are not installed
+        #[arg(long)]
+        all: bool,
+    },
+    /// Check the config, opener and shell completion setup for problems
+    Doctor {
+        /// Repair the problems that can be repaired automatically
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Check a config file for mistakes such as malformed URLs, duplicate targets, unknown
+    /// browsers and empty groups; fails if any are found, e.g. in a pre-commit hook
+    Lint {
+        /// Config file to check (default: ~/.config/web/config.toml)
+        #[arg(value_hint = ValueHint::FilePath)]
+        path: Option<std::path::PathBuf>,
+        /// Print the problems as a JSON array of {check, location, message} objects
+        #[arg(long)]
+        json: bool,
+    },
+    /// Register `web` as the handler for web://alias links
+    RegisterHandler,
+    /// Choose an alias interactively and open it
+    Pick,
+    /// Open the alias picker whenever a global keyboard shortcut is pressed
+    HotkeyDaemon {
+        /// Shortcut to listen for, e.g. ctrl+alt+w or cmd+shift+space
+        #[arg(long, default_value = "ctrl+alt+w")]
+        key: crate::hotkey::Hotkey,
+    },
     /// Generate shell completions
     Completions {
         #[arg(value_enum)]
         shell: clap_complete::Shell,
     },
     /// Export current alias settings to stdout (TOML format)
-    Export,
+    Export {
+        /// Instead write one macOS Shortcut per alias into this directory
+        #[arg(long, value_name = "DIR", value_hint = ValueHint::DirPath)]
+        shortcuts: Option<std::path::PathBuf>,
+        /// Only export aliases carrying this tag
+        #[arg(long, value_name = "TAG", add = ArgValueCompleter::new(complete_tag))]
+        tag: Option<String>,
+    },
     /// Import aliases from a TOML file (use `-` for stdin)
     Import {
         /// Path to the TOML file to import
         #[arg(value_hint = ValueHint::FilePath)]
         path: String,
     },
+    /// Manage secrets referenced as {secret:NAME} in alias URLs
+    #[command(subcommand)]
+    Secret(SecretCommand),
+    /// Open a randomly chosen alias
+    Random {
+        /// Only choose among aliases carrying this tag
+        #[arg(long, value_name = "TAG", add = ArgValueCompleter::new(complete_tag))]
+        tag: Option<String>,
+    },
+    /// Change the URL of an alias, keeping its settings; without a URL, edit the current one
+    Edit {
+        #[arg(add = ArgValueCompleter::new(complete_alias))]
+        alias: String,
+        #[arg(value_hint = ValueHint::Url)]
+        url: Option<String>,
+        /// Store the URL without checking that it is valid
+        #[arg(long)]
+        no_verify: bool,
+    },
+    /// Pin aliases so they come first in `web list`, completions and the picker
+    Pin {
+        #[arg(required = true, add = ArgValueCompleter::new(complete_alias))]
+        aliases: Vec<String>,
+    },
+    /// Unpin aliases, sorting them alphabetically again
+    Unpin {
+        #[arg(required = true, add = ArgValueCompleter::new(complete_alias))]
+        aliases: Vec<String>,
+    },
+    /// Copy an alias with all its settings to a new name
+    Cp {
+        #[arg(add = ArgValueCompleter::new(complete_alias))]
+        alias: String,
+        #[arg(value_hint = ValueHint::Other)]
+        new: String,
+    },
+    /// Rename an alias, keeping its settings and updating groups that include it
+    Rename {
+        #[arg(add = ArgValueCompleter::new(complete_alias))]
+        old: String,
+        #[arg(value_hint = ValueHint::Other)]
+        new: String,
+    },
+    /// Save a snapshot of an alias to the Wayback Machine and print its URL
+    Archive {
+        #[arg(add = ArgValueCompleter::new(complete_alias))]
+        alias: String,
+        /// Remember the snapshot URL on the alias
+        #[arg(long)]
+        store: bool,
+    },
+    /// Output recently used template arguments of an alias for shell completion (internal use)
+    #[command(name = "_complete-recent", hide = true)]
+    CompleteRecent { alias: String },
+    /// Output tags for shell completion (internal use)
+    #[command(name = "_complete-tags", hide = true)]
+    CompleteTags,
+    /// Output sessions for shell completion (internal use)
+    #[command(name = "_complete-sessions", hide = true)]
+    CompleteSessions,
+    /// Output removed aliases for shell completion (internal use)
+    #[command(name = "_complete-trash", hide = true)]
+    CompleteTrash,
+    /// Output groups for shell completion (internal use)
+    #[command(name = "_complete-groups", hide = true)]
+    CompleteGroups,
     /// Output aliases for shell completion (internal use)
     #[command(name = "_complete-aliases", hide = true)]
     CompleteAliases,
+    /// Output browser names for shell completion (internal use)
+    #[command(name = "_complete-browsers", hide = true)]
+    CompleteBrowsers,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Subcommand)]
+pub enum GroupCommand {
+    /// Create or replace a group — aliases comma-separated (e.g. gh,jira,ci)
+    Add {
+        /// Group name
+        name: String,
+        /// Aliases in the group, opened in this order
+        #[arg(value_hint = ValueHint::Other, add = ArgValueCompleter::new(complete_alias))]
+        aliases: String,
+    },
+    /// Open every alias in a group
+    Open {
+        #[arg(value_hint = ValueHint::Other, add = ArgValueCompleter::new(complete_group))]
+        name: String,
+    },
+    /// Remove a group (its aliases are kept)
+    Remove {
+        #[arg(value_hint = ValueHint::Other, add = ArgValueCompleter::new(complete_group))]
+        name: String,
+    },
+    /// List all groups
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TagCommand {
+    /// Tag an alias, e.g. `web tag add jira work ci`
+    Add {
+        #[arg(add = ArgValueCompleter::new(complete_alias))]
+        alias: String,
+        /// Tags to add; space- or comma-separated
+        #[arg(required = true, value_delimiter = ',', add = ArgValueCompleter::new(complete_tag))]
+        tags: Vec<String>,
+    },
+    /// Remove tags from an alias
+    #[command(visible_alias = "rm")]
+    Remove {
+        #[arg(add = ArgValueCompleter::new(complete_alias))]
+        alias: String,
+        /// Tags to remove; space- or comma-separated
+        #[arg(required = true, value_delimiter = ',', add = ArgValueCompleter::new(complete_tag))]
+        tags: Vec<String>,
+    },
+    /// Rename a tag on every alias carrying it
+    Rename {
+        #[arg(add = ArgValueCompleter::new(complete_tag))]
+        old: String,
+        #[arg(value_hint = ValueHint::Other)]
+        new: String,
+    },
+    /// Merge a tag into another: aliases tagged with the first get the second instead
+    Merge {
+        #[arg(add = ArgValueCompleter::new(complete_tag))]
+        from: String,
+        #[arg(add = ArgValueCompleter::new(complete_tag))]
+        into: String,
+    },
+    /// List all tags and the aliases carrying each
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TrashCommand {
+    /// List removed aliases and when they were removed
+    List,
+    /// Delete every removed alias for good
+    Empty,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SessionCommand {
+    /// Save URLs as a session, replacing any session of that name
+    Save {
+        /// Session name
+        name: String,
+        /// URLs to save; read from stdin (one per line) when omitted
+        #[arg(value_hint = ValueHint::Url)]
+        urls: Vec<String>,
+    },
+    /// Open every URL in a session
+    Open {
+        #[arg(value_hint = ValueHint::Other, add = ArgValueCompleter::new(complete_session))]
+        name: String,
+    },
+    /// Remove a session
+    Remove {
+        #[arg(value_hint = ValueHint::Other, add = ArgValueCompleter::new(complete_session))]
+        name: String,
+    },
+    /// List all sessions
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SecretCommand {
+    /// Store a secret in the OS keychain (the value is prompted for, or read from stdin)
+    Set { name: String },
+    /// Print a stored secret
+    Get { name: String },
+    /// Delete a stored secret
+    Remove { name: String },
+}
+
+/// Something done with a resolved URL in place of opening it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum UrlAction {
+    #[default]
+    Open,
+    /// Put it on the clipboard
+    Copy,
+    /// Draw it as a QR code in the terminal, or write it to a PNG file
+    Qr(Option<std::path::PathBuf>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BrowserChoice {
+    /// The system default browser
     Default,
-    Safari,
-    Chrome,
-    Firefox,
-    Brave,
+    /// A built-in browser or one defined in the `[browsers]` table of the config
+    Named(String),
+    /// A text browser run in the current terminal; `None` means `text_browser` from
+    /// the config or the first one found in `$PATH`
+    Text(Option<String>),
+}
+
+impl std::fmt::Display for BrowserChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BrowserChoice::Default => f.write_str("default browser"),
+            BrowserChoice::Named(name) | BrowserChoice::Text(Some(name)) => f.write_str(name),
+            BrowserChoice::Text(None) => f.write_str("text browser"),
+        }
+    }
+}
+
+impl std::str::FromStr for BrowserChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        if name.is_empty() {
+            return Err("browser name cannot be empty".to_string());
+        }
+        if name.eq_ignore_ascii_case("default") {
+            return Ok(BrowserChoice::Default);
+        }
+        // Unknown names may be defined in the config; they are checked when the URL is opened.
+        let name = builtin_browser(name).map_or(name, |spec| spec.name);
+        Ok(BrowserChoice::Named(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_delay_reads_units() {
+        assert_eq!(parse_delay("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_delay("1.5s"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_delay("250"), Ok(Duration::from_millis(250)));
+        assert!(parse_delay("soon").is_err());
+        assert!(parse_delay("-1s").is_err());
+    }
 }
@@ -0,0 +1,104 @@
+//! The system clipboard, through the command line tools of each platform.
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::browser::{find_in_path, Platform};
+
+/// Commands that print the clipboard contents, in order of preference.
+fn paste_commands() -> Vec<&'static [&'static str]> {
+    match Platform::detect() {
+        Platform::MacOs => vec![&["pbpaste"]],
+        Platform::Wsl => vec![&["powershell.exe", "-NoProfile", "-Command", "Get-Clipboard"]],
+        Platform::Linux => {
+            let x11: [&'static [&'static str]; 2] = [
+                &["xclip", "-selection", "clipboard", "-out"],
+                &["xsel", "--clipboard", "--output"],
+            ];
+            let wayland: &'static [&'static str] = &["wl-paste", "--no-newline"];
+            if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+                std::iter::once(wayland).chain(x11).collect()
+            } else {
+                x11.into_iter().chain(std::iter::once(wayland)).collect()
+            }
+        }
+    }
+}
+
+/// Commands that replace the clipboard contents with their input, in order of preference.
+fn copy_commands() -> Vec<&'static [&'static str]> {
+    match Platform::detect() {
+        Platform::MacOs => vec![&["pbcopy"]],
+        Platform::Wsl => vec![&["clip.exe"]],
+        Platform::Linux => {
+            let x11: [&'static [&'static str]; 2] = [
+                &["xclip", "-selection", "clipboard", "-in"],
+                &["xsel", "--clipboard", "--input"],
+            ];
+            let wayland: &'static [&'static str] = &["wl-copy"];
+            if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+                std::iter::once(wayland).chain(x11).collect()
+            } else {
+                x11.into_iter().chain(std::iter::once(wayland)).collect()
+            }
+        }
+    }
+}
+
+/// The first of `commands` that is installed.
+fn find_command(
+    commands: Vec<&'static [&'static str]>,
+    action: &str,
+) -> Result<&'static [&'static str]> {
+    match commands.iter().find(|c| find_in_path(c[0]).is_some()) {
+        Some(command) => Ok(command),
+        None => anyhow::bail!(
+            "Cannot {} the clipboard; install one of: {}",
+            action,
+            commands.iter().map(|c| c[0]).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+/// The text on the clipboard, without a trailing newline.
+pub fn read() -> Result<String> {
+    let command = find_command(paste_commands(), "read")?;
+    let output = Command::new(command[0])
+        .args(&command[1..])
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("Failed to run {}", command[0]))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Cannot read the clipboard: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    Ok(text.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Put `text` on the clipboard.
+pub fn write(text: &str) -> Result<()> {
+    let command = find_command(copy_commands(), "write to")?;
+    // xclip and xsel stay in the background to serve the clipboard, so don't wait on
+    // their output.
+    let mut child = Command::new(command[0])
+        .args(&command[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", command[0]))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .with_context(|| format!("Failed to write to {}", command[0]))?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        anyhow::bail!("{} exited with {:?}", command[0], status.code());
+    }
+    Ok(())
+}
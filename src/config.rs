@@ -4,10 +4,344 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+use crate::browser::Family;
+use crate::output::info;
+use crate::template;
+use crate::trace::{self, trace};
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Config {
-    #[serde(default)]
-    pub aliases: BTreeMap<String, String>,
+    /// Browser used when none is given on the command line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_browser: Option<String>,
+    /// Text browser used by `--tui-browser` (e.g. "w3m")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_browser: Option<String>,
+    /// Search alias used for input that is neither an alias nor a URL (e.g. "ddg")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_search: Option<String>,
+    /// Open the alias a prefix of its name stands for when no other alias starts with it
+    /// (`web gi` for `github`); a prefix of several aliases asks which one was meant
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub prefix_match: bool,
+    /// Match alias names regardless of case (`web GH` opens `gh`); names differing only in
+    /// case can't be added then
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ignore_case: bool,
+    /// URL schemes other than http(s) that may be opened, e.g. `["slack", "mailto", "vscode"]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_schemes: Option<Vec<String>>,
+    /// Allow opening `file:` URLs without --force
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_file_urls: bool,
+    /// Allow `{cmd:...}` placeholders in alias URLs to run commands
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_exec: bool,
+    /// Values substituted for `{vars.NAME}` in alias URLs, e.g. `org = "acme"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub vars: BTreeMap<String, String>,
+    /// Regex rules that turn input into a URL before alias lookup, in file order, e.g.
+    /// `"^JIRA-(\\d+)$" = "https://example.atlassian.net/browse/JIRA-$1"`
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "rewrite_list")]
+    pub rewrites: Vec<Rewrite>,
+    #[serde(default, with = "alias_map")]
+    pub aliases: BTreeMap<String, Alias>,
+    /// Aliases that shadow the global ones on one machine, keyed by hostname, e.g.
+    /// `[overrides.work-laptop]` with `ci = "https://jenkins.corp.example.com"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, HostAliases>,
+    /// User-defined browsers, selectable by name with `--browser`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub browsers: BTreeMap<String, CustomBrowser>,
+    /// Install locations of built-in browsers, e.g. `chrome = "/opt/google/chrome-beta/chrome"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub browser_paths: BTreeMap<String, String>,
+    /// Named sets of aliases opened together, e.g. `dev = ["gh", "jira", "ci"]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub groups: BTreeMap<String, Vec<String>>,
+    /// Days a removed alias stays in `[trash]` before it is purged; 0 keeps it until
+    /// `web trash empty` (default 30)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash_days: Option<u32>,
+    /// Removed aliases, restorable with `web restore` until `trash_days` have passed
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub trash: BTreeMap<String, TrashedAlias>,
+}
+
+impl Config {
+    /// The `[overrides]` aliases for this machine, matched by full or short hostname.
+    pub fn host_aliases(&self) -> Option<&BTreeMap<String, Alias>> {
+        self.host_overrides().map(|(_, aliases)| aliases)
+    }
+
+    /// `name` as compared with alias names: lowercased with `ignore_case`.
+    fn fold_case(&self, name: &str) -> String {
+        if self.ignore_case {
+            name.to_lowercase()
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Like `host_aliases`, with the name of the `[overrides]` table.
+    fn host_overrides(&self) -> Option<(&str, &BTreeMap<String, Alias>)> {
+        let host = hostname()?;
+        let short = host.split('.').next().unwrap_or(host);
+        let (name, aliases) = self
+            .overrides
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(host) || name.eq_ignore_ascii_case(short))?;
+        trace!("Using [overrides.{}] for host {}", name, host);
+        Some((name, &aliases.0))
+    }
+}
+
+/// Aliases of one `[overrides.<hostname>]` table.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct HostAliases(#[serde(with = "alias_map")] pub BTreeMap<String, Alias>);
+
+/// Name of this machine, looked up once.
+fn hostname() -> Option<&'static str> {
+    static HOSTNAME: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
+    HOSTNAME
+        .get_or_init(|| {
+            let output = std::process::Command::new("hostname").output().ok()?;
+            let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (output.status.success() && !name.is_empty()).then_some(name)
+        })
+        .as_deref()
+}
+
+/// An alias target. Written as a bare URL string in the config unless it carries
+/// extra settings, in which case it becomes a table: `jira = { url = "...", browser = "chrome" }`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Alias {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub url: String,
+    /// Several URLs, all opened together (or one per invocation with `rotate`). Written as a
+    /// bare list in the config: `mirrors = ["https://eu...", "https://us..."]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub urls: Vec<String>,
+    /// Open only the next of `urls` each time, cycling through them
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub rotate: bool,
+    /// URLs for other locales, e.g. `{ ja = "https://.../ja/" }`; `url` is the default
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub locales: BTreeMap<String, String>,
+    /// Browser to open this alias in unless overridden on the command line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser: Option<String>,
+    /// Browser profile to open this alias in (Chromium profile directory or Firefox profile name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Open as a chromeless app window (Chromium-based browsers)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub app: bool,
+    /// Ask before opening, e.g. for production consoles
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub confirm: bool,
+    /// Join extra command line words onto the URL path (`web gh rust-lang/rust`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub append_path: bool,
+    /// Wayback Machine snapshot of the URL, saved with `web archive --store`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive: Option<String>,
+    /// Free-form labels for opening related aliases together, e.g. `["news"]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Note on what the alias is for, shown by `web list --long` and in completions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Listed before the other aliases in `web list`, completions and the picker
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+}
+
+impl Alias {
+    pub fn new(url: impl Into<String>) -> Self {
+        Alias {
+            url: url.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the words after this alias on the command line are its arguments.
+    pub fn takes_args(&self) -> bool {
+        self.append_path || template::takes_args(&self.url)
+    }
+
+    /// The URL for `locale` (such as `ja_JP.UTF-8` or `pt-BR`), trying the full locale and
+    /// then the language alone.
+    pub fn locale_url(&self, locale: &str) -> Option<&str> {
+        let normalize = |l: &str| l.to_ascii_lowercase().replace('_', "-");
+        let full = normalize(locale.split(['.', '@']).next().unwrap_or(locale));
+        let language = full.split('-').next().unwrap_or(&full).to_string();
+        [full, language].iter().find_map(|wanted| {
+            self.locales
+                .iter()
+                .find(|(key, _)| normalize(key) == *wanted)
+                .map(|(_, url)| url.as_str())
+        })
+    }
+
+    /// The URL, or all of `urls` separated by spaces, for display.
+    pub fn display_url(&self) -> String {
+        if self.urls.is_empty() {
+            self.url.clone()
+        } else {
+            self.urls.join(" ")
+        }
+    }
+
+    fn is_plain(&self) -> bool {
+        *self == Alias::new(self.url.clone())
+    }
+
+    fn is_plain_list(&self) -> bool {
+        *self
+            == Alias {
+                urls: self.urls.clone(),
+                ..Default::default()
+            }
+    }
+}
+
+/// A removed alias in `[trash]`, with its settings and when it was removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedAlias {
+    /// Time of removal (RFC 3339)
+    pub deleted: String,
+    #[serde(flatten)]
+    pub alias: Alias,
+}
+
+/// (De)serializes alias maps, accepting the string, list and table forms of an entry.
+mod alias_map {
+    use super::Alias;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Url(String),
+        Urls(Vec<String>),
+        Table(Alias),
+    }
+
+    #[derive(Serialize)]
+    #[serde(untagged)]
+    enum EntryRef<'a> {
+        Url(&'a str),
+        Urls(&'a [String]),
+        Table(&'a Alias),
+    }
+
+    pub fn serialize<S: Serializer>(
+        map: &BTreeMap<String, Alias>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(map.iter().map(|(name, alias)| {
+            let entry = if alias.is_plain() {
+                EntryRef::Url(&alias.url)
+            } else if alias.is_plain_list() {
+                EntryRef::Urls(&alias.urls)
+            } else {
+                EntryRef::Table(alias)
+            };
+            (name, entry)
+        }))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<String, Alias>, D::Error> {
+        let raw = BTreeMap::<String, Entry>::deserialize(deserializer)?;
+        Ok(raw
+            .into_iter()
+            .map(|(name, entry)| {
+                let alias = match entry {
+                    Entry::Url(url) => Alias::new(url),
+                    Entry::Urls(urls) => Alias {
+                        urls,
+                        ..Default::default()
+                    },
+                    Entry::Table(alias) => alias,
+                };
+                (name, alias)
+            })
+            .collect())
+    }
+}
+
+/// A `[rewrites]` rule: input matching `pattern` opens `replacement`, in which `$1`,
+/// `${name}`, ... refer to capture groups.
+#[derive(Debug)]
+pub struct Rewrite {
+    pub pattern: regex::Regex,
+    pub replacement: String,
+}
+
+/// (De)serializes `[rewrites]`, keeping the rules in file order and rejecting invalid regexes
+/// when the config is loaded.
+mod rewrite_list {
+    use super::Rewrite;
+    use serde::de::{Error, MapAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt;
+
+    pub fn serialize<S: Serializer>(rules: &[Rewrite], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            rules
+                .iter()
+                .map(|rule| (rule.pattern.as_str(), &rule.replacement)),
+        )
+    }
+
+    struct RulesVisitor;
+
+    impl<'de> Visitor<'de> for RulesVisitor {
+        type Value = Vec<Rewrite>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a table of regex = replacement pairs")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut rules = Vec::new();
+            while let Some((pattern, replacement)) = map.next_entry::<String, String>()? {
+                let pattern = regex::Regex::new(&pattern).map_err(|e| {
+                    A::Error::custom(format!("invalid rewrite '{}': {}", pattern, e))
+                })?;
+                rules.push(Rewrite {
+                    pattern,
+                    replacement,
+                });
+            }
+            Ok(rules)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Rewrite>, D::Error> {
+        deserializer.deserialize_map(RulesVisitor)
+    }
+}
+
+/// A browser that is not built in, e.g. `floorp = { macos_app = "Floorp", linux_cmd = "floorp" }`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CustomBrowser {
+    /// Application name passed to `open -a` on macOS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub macos_app: Option<String>,
+    /// Command (optionally with leading arguments) run with the URL on Linux
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linux_cmd: Option<String>,
+    /// Engine family (`chromium`, `firefox` or `safari`), used to pick flags such as `--incognito`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<Family>,
 }
 
 pub fn config_path() -> Result<PathBuf> {
@@ -18,9 +352,16 @@ pub fn config_path() -> Result<PathBuf> {
 pub fn load() -> Result<Config> {
     let path = config_path()?;
     if !path.exists() {
+        trace!("No config file at {}", path.display());
         return Ok(Config::default());
     }
-    let content = std::fs::read_to_string(&path)
+    load_from(&path)
+}
+
+/// Read the config file at `path`, which must exist.
+pub fn load_from(path: &std::path::Path) -> Result<Config> {
+    trace!("Loading config from {}", path.display());
+    let content = std::fs::read_to_string(path)
         .with_context(|| format!("Failed to read config file at {}", path.display()))?;
     toml::from_str(&content).with_context(|| "Failed to parse config file")
 }
@@ -37,43 +378,816 @@ pub fn save(config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// Copy the config file next to itself with a timestamp, e.g. `config.toml.20240131-094500`,
+/// and return the copy's path.
+pub fn backup() -> Result<PathBuf> {
+    let path = config_path()?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let backup = path.with_file_name(format!("config.toml.{}", stamp));
+    std::fs::copy(&path, &backup)
+        .with_context(|| format!("Failed to back up config file to {}", backup.display()))?;
+    Ok(backup)
+}
+
 pub fn parse_aliases(aliases: &str) -> Vec<&str> {
     aliases.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).collect()
 }
 
-pub fn add_alias(aliases: &str, url: &str) -> Result<()> {
+pub fn add_alias(aliases: &str, entry: &Alias) -> Result<()> {
     let mut config = load()?;
     for alias in parse_aliases(aliases) {
-        config.aliases.insert(alias.to_string(), url.to_string());
+        check_case_collision(&config, alias)?;
+        config.aliases.insert(alias.to_string(), entry.clone());
+    }
+    save(&config)
+}
+
+/// With `ignore_case`, fails if `name` differs from an existing alias only in case.
+pub fn check_case_collision(config: &Config, name: &str) -> Result<()> {
+    let folded = config.fold_case(name);
+    if let Some(existing) = config
+        .aliases
+        .keys()
+        .find(|alias| *alias != name && config.fold_case(alias) == folded)
+    {
+        anyhow::bail!(
+            "'{}' differs from alias '{}' only in case, and ignore_case is set",
+            name,
+            existing
+        );
+    }
+    Ok(())
+}
+
+/// With `ignore_case`, groups of aliases whose names differ only in case.
+pub fn case_collisions(config: &Config) -> Vec<Vec<String>> {
+    let mut by_name: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for alias in config.aliases.keys() {
+        by_name.entry(config.fold_case(alias)).or_default().push(alias.clone());
     }
+    by_name.into_values().filter(|names| names.len() > 1).collect()
+}
+
+/// Move alias `old` to `new`, keeping its settings and updating groups, `@old` links and
+/// `fallback_search` that refer to it. Fails if `new` exists, unless `force` is set.
+pub fn rename_alias(old: &str, new: &str, force: bool) -> Result<()> {
+    let mut config = load()?;
+    let entry = config
+        .aliases
+        .remove(old)
+        .ok_or_else(|| anyhow!("Alias '{}' not found", old))?;
+    if config.aliases.contains_key(new) && !force {
+        anyhow::bail!("Alias '{}' already exists; pass --force to replace it", new);
+    }
+    check_case_collision(&config, new)?;
+    config.aliases.insert(new.to_string(), entry);
+    redirect_references(&mut config, old, new);
     save(&config)
 }
 
-pub fn remove_alias(aliases: &str) -> Result<()> {
+/// Make groups, `@old` links and `fallback_search` that refer to alias `old` refer to `new`.
+fn redirect_references(config: &mut Config, old: &str, new: &str) {
+    let link = format!("@{}", old);
+    for entry in config.aliases.values_mut() {
+        if entry.url == link {
+            entry.url = format!("@{}", new);
+        }
+    }
+    for members in config.groups.values_mut() {
+        for member in members.iter_mut() {
+            if member == old {
+                *member = new.to_string();
+            }
+        }
+        // A group holding both aliases would open the page twice.
+        let mut unique: Vec<String> = Vec::new();
+        for member in members.drain(..) {
+            if !unique.contains(&member) {
+                unique.push(member);
+            }
+        }
+        *members = unique;
+    }
+    if config.fallback_search.as_deref() == Some(old) {
+        config.fallback_search = Some(new.to_string());
+    }
+}
+
+/// Add `new` with the URL and settings of alias `from`. Fails if `new` exists, unless
+/// `force` is set.
+pub fn copy_alias(from: &str, new: &str, force: bool) -> Result<()> {
     let mut config = load()?;
-    for alias in parse_aliases(aliases) {
-        if config.aliases.remove(alias).is_none() {
-            anyhow::bail!("Alias '{}' not found", alias);
+    let entry = config
+        .aliases
+        .get(from)
+        .cloned()
+        .ok_or_else(|| anyhow!("Alias '{}' not found", from))?;
+    if config.aliases.contains_key(new) && !force {
+        anyhow::bail!("Alias '{}' already exists; pass --force to replace it", new);
+    }
+    check_case_collision(&config, new)?;
+    config.aliases.insert(new.to_string(), entry);
+    save(&config)
+}
+
+/// Change the settings of the existing alias `name` with `update`.
+pub fn update_alias(name: &str, update: impl FnOnce(&mut Alias)) -> Result<()> {
+    let mut config = load()?;
+    let entry = config
+        .aliases
+        .get_mut(name)
+        .ok_or_else(|| anyhow!("Alias '{}' not found", name))?;
+    update(entry);
+    save(&config)
+}
+
+/// The aliases named by comma-separated `aliases`, where a name containing `*` or `?` that
+/// isn't itself an alias (like the wildcard alias `gh/*`) is a glob such as `jira-*`.
+pub fn match_aliases(config: &Config, aliases: &str) -> Result<Vec<String>> {
+    let mut names: Vec<String> = Vec::new();
+    for pattern in parse_aliases(aliases) {
+        let matched: Vec<&String> = if config.aliases.contains_key(pattern) {
+            config.aliases.keys().filter(|a| *a == pattern).collect()
+        } else if pattern.contains(['*', '?']) {
+            config
+                .aliases
+                .keys()
+                .filter(|a| glob_match(pattern, a))
+                .collect()
+        } else {
+            anyhow::bail!("Alias '{}' not found", pattern);
+        };
+        if matched.is_empty() {
+            anyhow::bail!("No aliases match '{}'", pattern);
         }
+        for name in matched {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// Whether `alias` matches a `web list` pattern: with `*` or `?`, a glob matching its whole
+/// name or URL, and otherwise text found in either. Case is ignored.
+pub fn matches_pattern(pattern: &str, alias: &str, entry: &Alias) -> bool {
+    let pattern = pattern.to_lowercase();
+    let fields = [alias.to_lowercase(), entry.display_url().to_lowercase()];
+    if pattern.contains(['*', '?']) {
+        fields.iter().any(|field| glob_match(&pattern, field))
+    } else {
+        fields.iter().any(|field| field.contains(&pattern))
+    }
+}
+
+/// Whether `name` matches `pattern`, where `*` stands for any run of characters and `?` for
+/// a single one.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // Position after the last `*` seen in the pattern, and where it resumed in the name.
+    let (mut p, mut n, mut star) = (0, 0, None);
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, n));
+            p += 1;
+        } else if let Some((after_star, resumed)) = star {
+            p = after_star;
+            n = resumed + 1;
+            star = Some((after_star, n));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Move every alias to the trash, keeping the other settings. Returns how many were removed.
+pub fn remove_all_aliases() -> Result<usize> {
+    let mut config = load()?;
+    let names: Vec<String> = config.aliases.keys().cloned().collect();
+    move_to_trash(&mut config, &names);
+    save(&config)?;
+    Ok(names.len())
+}
+
+/// Move all of `names` to the trash, or none if one of them is not an alias.
+pub fn remove_alias(names: &[String]) -> Result<()> {
+    let mut config = load()?;
+    if let Some(missing) = names.iter().find(|a| !config.aliases.contains_key(*a)) {
+        anyhow::bail!("Alias '{}' not found", missing);
     }
+    move_to_trash(&mut config, names);
     save(&config)
 }
 
-pub fn resolve_alias(alias: &str) -> Result<String> {
-    let config = load()?;
+fn move_to_trash(config: &mut Config, names: &[String]) {
+    purge_trash(config);
+    let deleted = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+    for name in names {
+        if let Some(alias) = config.aliases.remove(name) {
+            let deleted = deleted.clone();
+            config.trash.insert(name.clone(), TrashedAlias { deleted, alias });
+        }
+    }
+}
+
+/// Drop trashed aliases removed more than `trash_days` ago. Returns whether any were dropped.
+fn purge_trash(config: &mut Config) -> bool {
+    let days = config.trash_days.unwrap_or(30);
+    if days == 0 {
+        return false;
+    }
+    let cutoff = chrono::Local::now() - chrono::Duration::days(i64::from(days));
+    let before = config.trash.len();
+    // Entries with an unreadable timestamp are kept rather than silently lost.
+    config.trash.retain(|_, trashed| {
+        chrono::DateTime::parse_from_rfc3339(&trashed.deleted).map_or(true, |t| t >= cutoff)
+    });
+    config.trash.len() != before
+}
+
+/// The aliases in the trash, after purging expired ones.
+pub fn list_trash() -> Result<BTreeMap<String, TrashedAlias>> {
+    let mut config = load()?;
+    if purge_trash(&mut config) {
+        save(&config)?;
+    }
+    Ok(config.trash)
+}
+
+/// Delete every alias in the trash for good. Returns how many were deleted.
+pub fn empty_trash() -> Result<usize> {
+    let mut config = load()?;
+    let count = config.trash.len();
+    config.trash.clear();
+    save(&config)?;
+    Ok(count)
+}
+
+/// Move alias `name` back from the trash. Fails if an alias of that name exists again,
+/// unless `force` is set.
+pub fn restore_alias(name: &str, force: bool) -> Result<()> {
+    let mut config = load()?;
+    purge_trash(&mut config);
+    let trashed = config
+        .trash
+        .remove(name)
+        .ok_or_else(|| anyhow!("Alias '{}' is not in the trash", name))?;
+    if config.aliases.contains_key(name) && !force {
+        anyhow::bail!("Alias '{}' already exists; pass --force to replace it", name);
+    }
+    config.aliases.insert(name.to_string(), trashed.alias);
+    save(&config)
+}
+
+pub fn resolve_alias(config: &Config, alias: &str) -> Result<Alias> {
+    if let Some(entry) = find_alias(config, alias)? {
+        return Ok(entry);
+    }
+    let matches = prefix_matches(config, alias);
+    if matches.len() > 1 {
+        anyhow::bail!("'{}' is the start of several aliases: {}", alias, matches.join(", "));
+    }
+    match suggest_alias(config, alias) {
+        Some(suggestion) => Err(anyhow!(
+            "Alias '{}' not found; did you mean '{}'?",
+            alias,
+            suggestion
+        )),
+        None => Err(anyhow!("Alias '{}' not found", alias)),
+    }
+}
+
+/// Apply the first `[rewrites]` rule matching `input`.
+fn rewrite(config: &Config, input: &str) -> Option<String> {
+    config.rewrites.iter().find_map(|rule| {
+        let captures = rule.pattern.captures(input)?;
+        let mut url = String::new();
+        captures.expand(&rule.replacement, &mut url);
+        trace!("Rewrite '{}' matched '{}': {}", rule.pattern, input, url);
+        Some(url)
+    })
+}
+
+/// Split input such as `rustbook#ch04-02` into an alias and the URL fragment to open it at,
+/// when the input as a whole isn't an alias but the part before the `#` is.
+pub fn split_fragment<'a>(config: &Config, input: &'a str) -> Result<(&'a str, Option<&'a str>)> {
+    if let Some((name, fragment)) = input.split_once('#') {
+        let is_fragment = trace::muted(|| -> Result<bool> {
+            Ok(find_alias(config, input)?.is_none() && find_alias(config, name)?.is_some())
+        })?;
+        if !name.is_empty() && is_fragment {
+            trace!("Opening alias '{}' at fragment #{}", name, fragment);
+            return Ok((name, Some(fragment)));
+        }
+    }
+    Ok((input, None))
+}
+
+/// Longest chain of `@alias` links followed before giving up.
+const MAX_LINKS: usize = 8;
+
+/// Look up `name`, first through `[rewrites]`, then as an alias, following `@other` links
+/// to the alias they point at. Fails on a link to a missing alias or a cycle of links.
+pub fn find_alias(config: &Config, name: &str) -> Result<Option<Alias>> {
+    if let Some(url) = rewrite(config, name) {
+        return Ok(Some(Alias::new(url)));
+    }
+    let found = lookup(config, name)
+        .map(|entry| (name.to_string(), entry))
+        .or_else(|| lookup_ignoring_case(config, name));
+    let found = match found {
+        Some(found) => Some(found),
+        None => lookup_namespaced(config, name)?.or_else(|| lookup_prefix(config, name)),
+    };
+    let Some((name, mut entry)) = found else {
+        return Ok(None);
+    };
+    let name = name.as_str();
+    let mut chain = vec![name.to_string()];
+    while let Some(target) = entry.url.strip_prefix('@').map(str::to_string) {
+        if chain.contains(&target) {
+            anyhow::bail!("Alias cycle: {} -> {}", chain.join(" -> "), target);
+        }
+        if chain.len() > MAX_LINKS {
+            anyhow::bail!(
+                "Alias '{}' links through more than {} aliases: {}",
+                name,
+                MAX_LINKS,
+                chain.join(" -> ")
+            );
+        }
+        let next = lookup(config, &target).ok_or_else(|| {
+            anyhow!(
+                "Alias '{}' points to '@{}', which is not an alias",
+                chain[chain.len() - 1],
+                target
+            )
+        })?;
+        trace!("'{}' links to '{}'", chain[chain.len() - 1], target);
+        chain.push(target);
+        // Settings on the link itself take precedence over those of its target.
+        entry = Alias {
+            browser: entry.browser.or(next.browser.clone()),
+            profile: entry.profile.or(next.profile.clone()),
+            ..next
+        };
+    }
+    Ok(Some(entry))
+}
+
+/// Look up `name` as an alias (this machine's `[overrides]` first) and then against
+/// wildcard aliases such as `gh/*`, substituting what the `*` matched into the URL. The
+/// pattern with the most literal characters wins.
+fn lookup(config: &Config, name: &str) -> Option<Alias> {
+    let host = config.host_aliases();
+    if let Some(entry) = host.and_then(|h| h.get(name)) {
+        trace!("'{}' is an alias of this host's [overrides]", name);
+        return Some(entry.clone());
+    }
+    if let Some(entry) = config.aliases.get(name) {
+        trace!("'{}' is an alias", name);
+        return Some(entry.clone());
+    }
+    // `max_by_key` keeps the last of equally specific patterns, so this machine's win.
     config
         .aliases
-        .get(alias)
+        .iter()
+        .chain(host.into_iter().flatten())
+        .filter_map(|(pattern, entry)| {
+            let (prefix, suffix) = pattern.split_once('*')?;
+            let rest = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            (!rest.is_empty()).then_some((prefix.len() + suffix.len(), pattern, rest, entry))
+        })
+        .max_by_key(|(literal_len, _, _, _)| *literal_len)
+        .map(|(_, pattern, rest, entry)| {
+            trace!("'{}' matched wildcard alias '{}'", name, pattern);
+            Alias {
+                url: entry.url.replace('*', rest),
+                ..entry.clone()
+            }
+        })
+}
+
+/// Look up `name` as the last part of a namespaced alias such as `work/jira`, which it
+/// resolves to when only one namespace defines it. Fails if several do.
+fn lookup_namespaced(config: &Config, name: &str) -> Result<Option<(String, Alias)>> {
+    if name.is_empty() || name.contains('/') {
+        return Ok(None);
+    }
+    // Collecting into a map lets this machine's overrides replace the global aliases.
+    let mut matches: BTreeMap<&String, &Alias> = config
+        .aliases
+        .iter()
+        .chain(config.host_aliases().into_iter().flatten())
+        .filter(|(key, _)| {
+            !key.contains('*')
+                && key
+                    .rsplit_once('/')
+                    .is_some_and(|(_, last)| config.fold_case(last) == config.fold_case(name))
+        })
+        .collect();
+    if matches.len() > 1 {
+        let keys: Vec<&str> = matches.keys().map(|k| k.as_str()).collect();
+        anyhow::bail!(
+            "'{}' is defined in several namespaces: {}; give the full name",
+            name,
+            keys.join(", ")
+        );
+    }
+    Ok(matches.pop_first().map(|(key, entry)| {
+        trace!("'{}' is '{}', the only alias of that name", name, key);
+        (key.clone(), entry.clone())
+    }))
+}
+
+/// With `ignore_case`, look up the alias (this machine's `[overrides]` first) whose name
+/// equals `name` apart from case.
+fn lookup_ignoring_case(config: &Config, name: &str) -> Option<(String, Alias)> {
+    if !config.ignore_case {
+        return None;
+    }
+    let folded = config.fold_case(name);
+    let alias = config
+        .host_aliases()
+        .into_iter()
+        .flat_map(|h| h.keys())
+        .chain(config.aliases.keys())
+        .find(|alias| config.fold_case(alias) == folded)?
+        .clone();
+    trace!("'{}' matches alias '{}' ignoring case", name, alias);
+    let entry = lookup(config, &alias)?;
+    Some((alias, entry))
+}
+
+/// With `prefix_match`, look up the one alias whose name starts with `prefix`.
+fn lookup_prefix(config: &Config, prefix: &str) -> Option<(String, Alias)> {
+    let [name] = prefix_matches(config, prefix).try_into().ok()?;
+    trace!("'{}' is the only alias starting with '{}'", name, prefix);
+    let entry = lookup(config, &name)?;
+    Some((name, entry))
+}
+
+/// With `prefix_match`, the aliases whose names start with `prefix`; none otherwise.
+pub fn prefix_matches(config: &Config, prefix: &str) -> Vec<String> {
+    if !config.prefix_match || prefix.is_empty() {
+        return Vec::new();
+    }
+    let mut names: Vec<String> = config
+        .aliases
+        .keys()
+        .chain(config.host_aliases().into_iter().flat_map(|h| h.keys()))
+        .filter(|alias| {
+            config.fold_case(alias).starts_with(&config.fold_case(prefix)) && !alias.contains('*')
+        })
         .cloned()
-        .ok_or_else(|| anyhow!("Alias '{}' not found", alias))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// The aliases in `namespace` (such as `work/`), with this machine's `[overrides]` in place
+/// of the global ones.
+pub fn namespace_aliases(config: &Config, namespace: &str) -> BTreeMap<String, Alias> {
+    let mut aliases = all_aliases(config);
+    aliases.retain(|alias, _| alias.starts_with(namespace) && !alias.contains('*'));
+    aliases
 }
 
-pub fn list_aliases() -> Result<Vec<(String, String)>> {
+/// Aliases registered for `url`, as their URL or one of their `urls`, ignoring a trailing
+/// slash and tracking parameters.
+pub fn aliases_for_url(config: &Config, url: &str) -> Vec<String> {
+    let url = comparable_url(url);
+    config
+        .aliases
+        .iter()
+        .filter(|(_, entry)| {
+            std::iter::once(&entry.url)
+                .chain(&entry.urls)
+                .any(|u| comparable_url(u) == url)
+        })
+        .map(|(alias, _)| alias.clone())
+        .collect()
+}
+
+/// Query parameters that only record where a link was shared.
+fn is_tracking_param(name: &str) -> bool {
+    name.starts_with("utm_") || matches!(name, "fbclid" | "gclid" | "mc_cid" | "mc_eid")
+}
+
+/// `url` reduced to what identifies the page, for finding duplicates: without a trailing
+/// slash or tracking parameters, and with the scheme and host in lowercase.
+fn comparable_url(url: &str) -> String {
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return url.trim_end_matches('/').to_string();
+    };
+    if parsed.query().is_some() {
+        let kept: Vec<(String, String)> = parsed
+            .query_pairs()
+            .filter(|(name, _)| !is_tracking_param(name))
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        if kept.is_empty() {
+            parsed.set_query(None);
+        } else {
+            parsed.query_pairs_mut().clear().extend_pairs(kept);
+        }
+    }
+    let path = parsed.path().trim_end_matches('/').to_string();
+    parsed.set_path(&path);
+    parsed.to_string().trim_end_matches('/').to_string()
+}
+
+/// Groups of aliases whose URLs point at the same page by `comparable_url`. Links to other
+/// aliases and wildcard aliases are left out.
+pub fn duplicate_aliases(config: &Config) -> Vec<Vec<String>> {
+    let mut by_url: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (alias, entry) in &config.aliases {
+        if entry.url.starts_with('@') || alias.contains('*') {
+            continue;
+        }
+        let urls: Vec<&String> = if entry.urls.is_empty() {
+            vec![&entry.url]
+        } else {
+            entry.urls.iter().collect()
+        };
+        let key: Vec<String> = urls.into_iter().map(|url| comparable_url(url)).collect();
+        by_url.entry(key.join(" ")).or_default().push(alias.clone());
+    }
+    by_url.into_values().filter(|names| names.len() > 1).collect()
+}
+
+/// Keep alias `keep` and move `others` to the trash, giving `keep` their tags, and their
+/// description if it has none. Groups, `@` links and `fallback_search` naming one of
+/// `others` name `keep` instead.
+pub fn merge_aliases(keep: &str, others: &[String]) -> Result<()> {
+    let mut config = load()?;
+    if let Some(missing) = others
+        .iter()
+        .map(String::as_str)
+        .chain([keep])
+        .find(|a| !config.aliases.contains_key(*a))
+    {
+        anyhow::bail!("Alias '{}' not found", missing);
+    }
+    let mut merged = config.aliases[keep].clone();
+    for other in others {
+        let entry = &config.aliases[other];
+        for tag in &entry.tags {
+            if !merged.tags.contains(tag) {
+                merged.tags.push(tag.clone());
+            }
+        }
+        if merged.description.is_none() {
+            merged.description = entry.description.clone();
+        }
+        merged.pinned |= entry.pinned;
+    }
+    config.aliases.insert(keep.to_string(), merged);
+    move_to_trash(&mut config, others);
+    for other in others {
+        redirect_references(&mut config, other, keep);
+    }
+    save(&config)
+}
+
+/// The registered alias closest to `name`, if it is close enough to be a likely typo.
+pub fn suggest_alias(config: &Config, name: &str) -> Option<String> {
+    let max_distance = if name.chars().count() <= 4 { 1 } else { 2 };
+    config
+        .aliases
+        .keys()
+        .map(|alias| (edit_distance(alias, name), alias))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, alias)| alias.clone())
+}
+
+/// Levenshtein distance between `a` and `b`, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+pub fn add_group(name: &str, aliases: &str) -> Result<()> {
+    let mut config = load()?;
+    let members = parse_aliases(aliases);
+    if members.is_empty() {
+        anyhow::bail!("Group '{}' needs at least one alias", name);
+    }
+    for alias in &members {
+        if find_alias(&config, alias)?.is_none() {
+            anyhow::bail!("Alias '{}' not found", alias);
+        }
+    }
+    config.groups.insert(
+        name.to_string(),
+        members.into_iter().map(String::from).collect(),
+    );
+    save(&config)
+}
+
+pub fn remove_group(name: &str) -> Result<()> {
+    let mut config = load()?;
+    if config.groups.remove(name).is_none() {
+        anyhow::bail!("Group '{}' not found", name);
+    }
+    save(&config)
+}
+
+pub fn resolve_group(config: &Config, name: &str) -> Result<Vec<String>> {
+    config
+        .groups
+        .get(name)
+        .cloned()
+        .ok_or_else(|| anyhow!("Group '{}' not found", name))
+}
+
+/// Names of all aliases tagged with `tag`.
+pub fn aliases_with_tag(config: &Config, tag: &str) -> Vec<String> {
+    config
+        .aliases
+        .iter()
+        .filter(|(_, entry)| entry.tags.iter().any(|t| t == tag))
+        .map(|(alias, _)| alias.clone())
+        .collect()
+}
+
+/// Every tag in use, with the aliases carrying it.
+pub fn list_tags() -> Result<BTreeMap<String, Vec<String>>> {
     let config = load()?;
-    Ok(config
+    let mut tags: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (alias, entry) in &config.aliases {
+        for tag in &entry.tags {
+            tags.entry(tag.clone()).or_default().push(alias.clone());
+        }
+    }
+    Ok(tags)
+}
+
+/// Replace tag `old` with `new` on every alias carrying it, including those of
+/// `[overrides]`. Fails if no alias carries `old`, or, unless `merge` is set, if one
+/// already carries `new`. Returns the number of aliases changed.
+pub fn rename_tag(old: &str, new: &str, merge: bool) -> Result<usize> {
+    let mut config = load()?;
+    let changed = retag(&mut config, old, new, merge)?;
+    save(&config)?;
+    Ok(changed)
+}
+
+/// `rename_tag` on a loaded config.
+fn retag(config: &mut Config, old: &str, new: &str, merge: bool) -> Result<usize> {
+    if old == new {
+        anyhow::bail!("Both tags are '{}'", old);
+    }
+    let tables = std::iter::once(&mut config.aliases)
+        .chain(config.overrides.values_mut().map(|host| &mut host.0));
+    let entries: Vec<&mut Alias> = tables.flat_map(|aliases| aliases.values_mut()).collect();
+    if !entries.iter().any(|e| e.tags.iter().any(|t| t == old)) {
+        anyhow::bail!("No aliases tagged '{}'", old);
+    }
+    if !merge && entries.iter().any(|e| e.tags.iter().any(|t| t == new)) {
+        anyhow::bail!(
+            "Tag '{}' is already in use; `web tag merge {} {}` combines the two",
+            new,
+            old,
+            new
+        );
+    }
+    let mut changed = 0;
+    for entry in entries {
+        let Some(i) = entry.tags.iter().position(|t| t == old) else {
+            continue;
+        };
+        if entry.tags.iter().any(|t| t == new) {
+            entry.tags.remove(i);
+        } else {
+            entry.tags[i] = new.to_string();
+        }
+        changed += 1;
+    }
+    Ok(changed)
+}
+
+/// Add `tags` to alias `name`, skipping ones it already has. Returns the tags added.
+pub fn tag_alias(name: &str, tags: &[String]) -> Result<Vec<String>> {
+    let mut added = Vec::new();
+    update_alias(name, |entry| {
+        for tag in tags {
+            if !entry.tags.contains(tag) && !added.contains(tag) {
+                entry.tags.push(tag.clone());
+                added.push(tag.clone());
+            }
+        }
+    })?;
+    Ok(added)
+}
+
+/// Remove `tags` from alias `name`. Fails, changing nothing, if it lacks one of them.
+pub fn untag_alias(name: &str, tags: &[String]) -> Result<()> {
+    let mut config = load()?;
+    let entry = config
         .aliases
-        .into_iter()
-        .collect())
+        .get_mut(name)
+        .ok_or_else(|| anyhow!("Alias '{}' not found", name))?;
+    if let Some(missing) = tags.iter().find(|t| !entry.tags.contains(t)) {
+        anyhow::bail!("Alias '{}' is not tagged '{}'", name, missing);
+    }
+    entry.tags.retain(|t| !tags.contains(t));
+    save(&config)
+}
+
+/// Pin or unpin the aliases `names`. Fails, changing nothing, if one of them doesn't exist.
+pub fn set_pinned(names: &[String], pinned: bool) -> Result<()> {
+    let mut config = load()?;
+    if let Some(missing) = names.iter().find(|a| !config.aliases.contains_key(*a)) {
+        anyhow::bail!("Alias '{}' not found", missing);
+    }
+    for name in names {
+        if let Some(entry) = config.aliases.get_mut(name) {
+            entry.pinned = pinned;
+        }
+    }
+    save(&config)
+}
+
+/// `aliases` in display order: pinned ones first, each part alphabetical.
+pub fn pinned_first(aliases: BTreeMap<String, Alias>) -> Vec<(String, Alias)> {
+    let mut aliases: Vec<(String, Alias)> = aliases.into_iter().collect();
+    aliases.sort_by_key(|(_, entry)| !entry.pinned);
+    aliases
+}
+
+/// The alias `name` as written in the config, without following links, and the name of the
+/// `[overrides]` table it comes from when this machine overrides it.
+pub fn get_alias(config: &Config, name: &str) -> Option<(Alias, Option<String>)> {
+    if let Some((table, host)) = config.host_overrides() {
+        if let Some(entry) = host.get(name) {
+            return Some((entry.clone(), Some(table.to_string())));
+        }
+    }
+    config.aliases.get(name).map(|entry| (entry.clone(), None))
+}
+
+/// Aliases with a URL matching `pattern`, as (alias, URL) pairs; aliases of `[overrides]`
+/// tables (for any machine) are named like `ci [overrides.work-laptop]`.
+pub fn grep_aliases(pattern: &regex::Regex) -> Result<Vec<(String, String)>> {
+    let config = load()?;
+    let tables = std::iter::once((None, &config.aliases)).chain(
+        config
+            .overrides
+            .iter()
+            .map(|(host, aliases)| (Some(host), &aliases.0)),
+    );
+    let mut matches = Vec::new();
+    for (host, aliases) in tables {
+        for (alias, entry) in aliases {
+            let name = match host {
+                Some(host) => format!("{} [overrides.{}]", alias, host),
+                None => alias.clone(),
+            };
+            let urls = std::iter::once(&entry.url)
+                .chain(&entry.urls)
+                .chain(entry.locales.values());
+            for url in urls.filter(|url| pattern.is_match(url)) {
+                matches.push((name.clone(), url.clone()));
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// Every alias, with this machine's `[overrides]` in place of the global ones, only those
+/// tagged `tag` if given.
+pub fn list_aliases(tag: Option<&str>) -> Result<BTreeMap<String, Alias>> {
+    let mut aliases = all_aliases(&load()?);
+    aliases.retain(|_, entry| tag.is_none_or(|tag| entry.tags.iter().any(|t| t == tag)));
+    Ok(aliases)
+}
+
+/// The global aliases together with this machine's `[overrides]`, which take precedence.
+pub fn all_aliases(config: &Config) -> BTreeMap<String, Alias> {
+    let mut aliases = config.aliases.clone();
+    if let Some(host) = config.host_aliases() {
+        aliases.extend(host.clone());
+    }
+    aliases
 }
 
 pub fn import_aliases(path: &str) -> Result<()> {
@@ -93,33 +1207,33 @@ pub fn import_aliases(path: &str) -> Result<()> {
         toml::from_str(&content).with_context(|| "Failed to parse TOML input")?;
 
     if imported.aliases.is_empty() {
-        println!("No aliases found in input.");
+        info!("No aliases found in input.");
         return Ok(());
     }
 
     let mut config = load()?;
 
-    let mut new_aliases: Vec<(String, String)> = Vec::new();
-    let mut conflicts: Vec<(String, String, String)> = Vec::new(); // (alias, existing_url, imported_url)
+    let mut new_aliases: Vec<(String, Alias)> = Vec::new();
+    let mut conflicts: Vec<(String, Alias, Alias)> = Vec::new(); // (alias, existing, imported)
     let mut unchanged: usize = 0;
 
-    for (alias, imported_url) in &imported.aliases {
+    for (alias, imported_entry) in &imported.aliases {
         match config.aliases.get(alias) {
-            Some(existing_url) if existing_url == imported_url => {
+            Some(existing) if existing == imported_entry => {
                 unchanged += 1;
             }
-            Some(existing_url) => {
-                conflicts.push((alias.clone(), existing_url.clone(), imported_url.clone()));
+            Some(existing) => {
+                conflicts.push((alias.clone(), existing.clone(), imported_entry.clone()));
             }
             None => {
-                new_aliases.push((alias.clone(), imported_url.clone()));
+                new_aliases.push((alias.clone(), imported_entry.clone()));
             }
         }
     }
 
     // Apply new aliases directly
-    for (alias, url) in &new_aliases {
-        config.aliases.insert(alias.clone(), url.clone());
+    for (alias, entry) in &new_aliases {
+        config.aliases.insert(alias.clone(), entry.clone());
     }
 
     // Resolve conflicts interactively
@@ -127,10 +1241,10 @@ pub fn import_aliases(path: &str) -> Result<()> {
     let mut skipped: usize = 0;
     let mut bulk_action: Option<bool> = None; // Some(true) = use all imported, Some(false) = keep all existing
 
-    for (alias, existing_url, imported_url) in &conflicts {
+    for (alias, existing, imported_entry) in &conflicts {
         if let Some(use_imported) = bulk_action {
             if use_imported {
-                config.aliases.insert(alias.clone(), imported_url.clone());
+                config.aliases.insert(alias.clone(), imported_entry.clone());
                 overwritten += 1;
             } else {
                 skipped += 1;
@@ -140,20 +1254,20 @@ pub fn import_aliases(path: &str) -> Result<()> {
 
         let prompt = format!(
             "Conflict for '{}':\n  current:  {}\n  imported: {}",
-            alias, existing_url, imported_url
+            alias, existing.url, imported_entry.url
         );
         let remaining = conflicts.len() - overwritten - skipped;
         let items = if remaining > 1 {
             vec![
-                format!("Keep existing ({})", existing_url),
-                format!("Use imported ({})", imported_url),
+                format!("Keep existing ({})", existing.url),
+                format!("Use imported ({})", imported_entry.url),
                 "Keep all existing".to_string(),
                 "Use all imported".to_string(),
             ]
         } else {
             vec![
-                format!("Keep existing ({})", existing_url),
-                format!("Use imported ({})", imported_url),
+                format!("Keep existing ({})", existing.url),
+                format!("Use imported ({})", imported_entry.url),
             ]
         };
 
@@ -168,7 +1282,7 @@ pub fn import_aliases(path: &str) -> Result<()> {
                 skipped += 1;
             }
             1 => {
-                config.aliases.insert(alias.clone(), imported_url.clone());
+                config.aliases.insert(alias.clone(), imported_entry.clone());
                 overwritten += 1;
             }
             2 => {
@@ -178,7 +1292,7 @@ pub fn import_aliases(path: &str) -> Result<()> {
             }
             3 => {
                 // Use all imported
-                config.aliases.insert(alias.clone(), imported_url.clone());
+                config.aliases.insert(alias.clone(), imported_entry.clone());
                 overwritten += 1;
                 bulk_action = Some(true);
             }
@@ -204,15 +1318,43 @@ pub fn import_aliases(path: &str) -> Result<()> {
         parts.push(format!("{} unchanged", unchanged));
     }
     if parts.is_empty() {
-        println!("Nothing to import.");
+        info!("Nothing to import.");
     } else {
-        println!("Import complete: {}.", parts.join(", "));
+        info!("Import complete: {}.", parts.join(", "));
     }
 
     Ok(())
 }
 
 pub fn complete_alias(current: &std::ffi::OsStr) -> Vec<clap_complete::engine::CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return vec![];
+    };
+    let Ok(aliases) = list_aliases(None) else {
+        return vec![];
+    };
+    // Namespaces such as `work/` come first; `web work/` picks one of their aliases.
+    let mut namespaces: BTreeMap<String, usize> = BTreeMap::new();
+    for alias in aliases.keys().filter(|a| a.starts_with(current)) {
+        if let Some(i) = alias[current.len()..].find('/') {
+            *namespaces.entry(alias[..=current.len() + i].to_string()).or_default() += 1;
+        }
+    }
+    let namespaces = namespaces.into_iter().map(|(namespace, count)| {
+        let help = format!("{} alias(es)", count);
+        clap_complete::engine::CompletionCandidate::new(namespace).help(Some(help.into()))
+    });
+    let aliases = pinned_first(aliases)
+        .into_iter()
+        .filter(|(alias, _)| alias.starts_with(current))
+        .map(|(alias, entry)| {
+            let help = entry.description.clone().unwrap_or_else(|| entry.display_url());
+            clap_complete::engine::CompletionCandidate::new(alias).help(Some(help.into()))
+        });
+    namespaces.chain(aliases).collect()
+}
+
+pub fn complete_group(current: &std::ffi::OsStr) -> Vec<clap_complete::engine::CompletionCandidate> {
     let Some(current) = current.to_str() else {
         return vec![];
     };
@@ -220,9 +1362,285 @@ pub fn complete_alias(current: &std::ffi::OsStr) -> Vec<clap_complete::engine::C
         return vec![];
     };
     config
-        .aliases
+        .groups
         .into_keys()
-        .filter(|alias| alias.starts_with(current))
+        .filter(|group| group.starts_with(current))
         .map(clap_complete::engine::CompletionCandidate::new)
         .collect()
 }
+
+pub fn complete_trash(current: &std::ffi::OsStr) -> Vec<clap_complete::engine::CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return vec![];
+    };
+    let Ok(config) = load() else {
+        return vec![];
+    };
+    config
+        .trash
+        .into_keys()
+        .filter(|name| name.starts_with(current))
+        .map(clap_complete::engine::CompletionCandidate::new)
+        .collect()
+}
+
+pub fn complete_tag(current: &std::ffi::OsStr) -> Vec<clap_complete::engine::CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return vec![];
+    };
+    let Ok(tags) = list_tags() else {
+        return vec![];
+    };
+    tags.into_keys()
+        .filter(|tag| tag.starts_with(current))
+        .map(clap_complete::engine::CompletionCandidate::new)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> Config {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn url_of(config: &Config, name: &str) -> Option<String> {
+        find_alias(config, name).unwrap().map(|entry| entry.url)
+    }
+
+    #[test]
+    fn find_alias_matches_the_most_specific_wildcard() {
+        let config = parse(
+            r#"
+            [aliases]
+            "gh/*" = "https://github.com/*"
+            "gh/rust-lang/*" = "https://github.com/rust-lang/*/issues"
+            "gh/new" = "https://github.com/new"
+            "#,
+        );
+        assert_eq!(url_of(&config, "gh/tokio").unwrap(), "https://github.com/tokio");
+        assert_eq!(
+            url_of(&config, "gh/rust-lang/cargo").unwrap(),
+            "https://github.com/rust-lang/cargo/issues"
+        );
+        assert_eq!(url_of(&config, "gh/new").unwrap(), "https://github.com/new");
+        assert_eq!(url_of(&config, "gh/"), None);
+    }
+
+    #[test]
+    fn glob_match_handles_stars_and_question_marks() {
+        assert!(glob_match("gh-*", "gh-issues"));
+        assert!(glob_match("gh-*", "gh-"));
+        assert!(glob_match("*.net/*", "jira.atlassian.net/browse"));
+        assert!(glob_match("j?ra", "jira"));
+        assert!(!glob_match("j?ra", "jra"));
+        assert!(!glob_match("gh-*", "ghe"));
+    }
+
+    #[test]
+    fn find_alias_follows_links() {
+        let config = parse(
+            r#"
+            [aliases]
+            gh = { url = "https://github.com", browser = "firefox", profile = "work" }
+            hub = { url = "@gh", browser = "chrome" }
+            code = "@hub"
+            "#,
+        );
+        let entry = find_alias(&config, "code").unwrap().unwrap();
+        assert_eq!(entry.url, "https://github.com");
+        // The nearest setting along the chain wins.
+        assert_eq!(entry.browser.as_deref(), Some("chrome"));
+        assert_eq!(entry.profile.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn find_alias_rejects_cycles_and_dangling_links() {
+        let config = parse(
+            r#"
+            [aliases]
+            a = "@b"
+            b = "@c"
+            c = "@a"
+            d = "@missing"
+            "#,
+        );
+        let err = find_alias(&config, "a").unwrap_err().to_string();
+        assert_eq!(err, "Alias cycle: a -> b -> c -> a");
+        let err = find_alias(&config, "d").unwrap_err().to_string();
+        assert!(err.contains("'@missing', which is not an alias"), "{err}");
+    }
+
+    #[test]
+    fn find_alias_limits_the_length_of_link_chains() {
+        let mut config = Config::default();
+        for i in 0..=MAX_LINKS + 1 {
+            config
+                .aliases
+                .insert(format!("l{i}"), Alias::new(format!("@l{}", i + 1)));
+        }
+        let end = format!("l{}", MAX_LINKS + 2);
+        config.aliases.insert(end, Alias::new("https://example.com"));
+        let err = find_alias(&config, "l0").unwrap_err().to_string();
+        assert!(err.contains("links through more than"), "{err}");
+        // From l2 the chain is exactly MAX_LINKS links long.
+        assert!(find_alias(&config, "l2").unwrap().is_some());
+    }
+
+    #[test]
+    fn find_alias_opens_a_unique_prefix() {
+        let aliases = r#"
+            [aliases]
+            jira = "https://jira"
+            gh = "https://github.com"
+            ghe = "https://github.example.com"
+            "gh/*" = "https://github.com/*"
+            "#;
+        let config = parse(&format!("prefix_match = true\n{aliases}"));
+        assert_eq!(url_of(&config, "ji").unwrap(), "https://jira");
+        assert_eq!(url_of(&config, "gh").unwrap(), "https://github.com");
+        assert_eq!(url_of(&config, "g"), None);
+        assert_eq!(prefix_matches(&config, "g"), ["gh", "ghe"]);
+        let err = resolve_alias(&config, "g").unwrap_err().to_string();
+        assert_eq!(err, "'g' is the start of several aliases: gh, ghe");
+
+        let config = parse(aliases);
+        assert_eq!(url_of(&config, "ji"), None);
+        assert!(prefix_matches(&config, "g").is_empty());
+    }
+
+    #[test]
+    fn split_fragment_only_splits_off_alias_fragments() {
+        let config = parse(
+            r#"
+            [aliases]
+            rustbook = "https://doc.rust-lang.org/book/"
+            "c#" = "https://learn.microsoft.com/dotnet/csharp/"
+            "#,
+        );
+        let split = |input| split_fragment(&config, input).unwrap();
+        assert_eq!(split("rustbook#ch04"), ("rustbook", Some("ch04")));
+        assert_eq!(split("rustbook"), ("rustbook", None));
+        assert_eq!(split("c#"), ("c#", None));
+        assert_eq!(split("nope#x"), ("nope#x", None));
+        assert_eq!(split("#x"), ("#x", None));
+    }
+
+    #[test]
+    fn match_aliases_expands_globs() {
+        let config = parse(
+            r#"
+            [aliases]
+            jira-a = "https://a"
+            jira-b = "https://b"
+            "gh/*" = "https://github.com/*"
+            wiki = "https://w"
+            "#,
+        );
+        let names = match_aliases(&config, "wiki, jira-*, jira-a").unwrap();
+        assert_eq!(names, ["wiki", "jira-a", "jira-b"]);
+        // A wildcard alias is named as itself rather than matched as a glob.
+        assert_eq!(match_aliases(&config, "gh/*").unwrap(), ["gh/*"]);
+        let err = match_aliases(&config, "x?").unwrap_err().to_string();
+        assert_eq!(err, "No aliases match 'x?'");
+        let err = match_aliases(&config, "jira").unwrap_err().to_string();
+        assert_eq!(err, "Alias 'jira' not found");
+    }
+
+    #[test]
+    fn purge_trash_drops_expired_entries() {
+        let days_ago = |days| {
+            (chrono::Local::now() - chrono::Duration::days(days))
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+        };
+        let trashed = |deleted: String| TrashedAlias {
+            deleted,
+            alias: Alias::new("https://example.com"),
+        };
+        let mut config = Config {
+            trash_days: Some(7),
+            ..Default::default()
+        };
+        config.trash.insert("old".into(), trashed(days_ago(10)));
+        config.trash.insert("recent".into(), trashed(days_ago(1)));
+        config.trash.insert("unreadable".into(), trashed("yesterday".into()));
+        assert!(purge_trash(&mut config));
+        assert_eq!(
+            config.trash.keys().collect::<Vec<_>>(),
+            ["recent", "unreadable"]
+        );
+        assert!(!purge_trash(&mut config));
+
+        config.trash_days = Some(0);
+        config.trash.insert("old".into(), trashed(days_ago(1000)));
+        assert!(!purge_trash(&mut config));
+        assert_eq!(config.trash.len(), 3);
+    }
+
+    #[test]
+    fn move_to_trash_keeps_the_alias_settings() {
+        let mut config = parse(
+            r#"
+            [aliases]
+            gh = { url = "https://github.com", tags = ["code"] }
+            wiki = "https://w"
+            "#,
+        );
+        move_to_trash(&mut config, &["gh".to_string(), "missing".to_string()]);
+        assert_eq!(config.aliases.keys().collect::<Vec<_>>(), ["wiki"]);
+        assert_eq!(config.trash["gh"].alias.tags, ["code"]);
+        assert!(!config.trash.contains_key("missing"));
+    }
+
+    #[test]
+    fn retag_renames_and_merges_tags() {
+        let mut config = parse(
+            r#"
+            [aliases]
+            a = { url = "https://a", tags = ["wrk", "x"] }
+            b = { url = "https://b", tags = ["work", "wrk"] }
+            c = { url = "https://c", tags = ["work"] }
+            "#,
+        );
+        let err = retag(&mut config, "wrk", "work", false).unwrap_err();
+        assert!(err.to_string().contains("already in use"), "{err}");
+        assert_eq!(retag(&mut config, "wrk", "work", true).unwrap(), 2);
+        assert_eq!(config.aliases["a"].tags, ["work", "x"]);
+        assert_eq!(config.aliases["b"].tags, ["work"]);
+        assert_eq!(retag(&mut config, "x", "misc", false).unwrap(), 1);
+        assert_eq!(config.aliases["a"].tags, ["work", "misc"]);
+        let err = retag(&mut config, "wrk", "job", false).unwrap_err();
+        assert_eq!(err.to_string(), "No aliases tagged 'wrk'");
+        assert!(retag(&mut config, "work", "work", true).is_err());
+    }
+
+    #[test]
+    fn aliases_for_url_checks_every_url() {
+        let mut config = Config::default();
+        config
+            .aliases
+            .insert("gh".to_string(), Alias::new("https://github.com/"));
+        let list = Alias {
+            urls: vec!["https://a.example".into(), "https://b.example".into()],
+            ..Default::default()
+        };
+        config.aliases.insert("both".to_string(), list);
+        assert_eq!(aliases_for_url(&config, "https://github.com"), ["gh"]);
+        assert_eq!(aliases_for_url(&config, "https://b.example/"), ["both"]);
+        assert!(aliases_for_url(&config, "https://c.example").is_empty());
+    }
+
+    #[test]
+    fn comparable_url_ignores_tracking_and_trailing_slashes() {
+        assert_eq!(
+            comparable_url("HTTPS://Example.com/docs/?utm_source=x&page=2"),
+            "https://example.com/docs?page=2"
+        );
+        assert_eq!(
+            comparable_url("https://example.com/?fbclid=1"),
+            "https://example.com"
+        );
+        assert_eq!(comparable_url("not a url/"), "not a url");
+    }
+}
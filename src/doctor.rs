@@ -0,0 +1,360 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::browser;
+use crate::cli::Cli;
+use crate::config::{self, Config};
+use crate::output::info;
+use crate::template;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+/// Collects check results and prints them as they come in.
+#[derive(Default)]
+struct Report {
+    warnings: usize,
+    failures: usize,
+}
+
+impl Report {
+    fn check(&mut self, status: Status, message: impl AsRef<str>) {
+        let tag = match status {
+            Status::Ok => "[ok]  ",
+            Status::Warn => {
+                self.warnings += 1;
+                "[warn]"
+            }
+            Status::Fail => {
+                self.failures += 1;
+                "[fail]"
+            }
+        };
+        println!("{} {}", tag, message.as_ref());
+    }
+
+    fn fixed(&mut self, message: impl AsRef<str>) {
+        println!("[fix]  {}", message.as_ref());
+    }
+}
+
+/// Run all checks, repairing what can be repaired when `fix` is set.
+pub fn run(fix: bool) -> Result<()> {
+    let mut report = Report::default();
+    let path = config::config_path()?;
+
+    check_config_dir(&mut report, &path, fix);
+    let mut config = check_config_file(&mut report, &path);
+    if let Some(config) = &mut config {
+        if check_urls(&mut report, config, fix) {
+            config::save(config)?;
+            report.fixed(format!("Saved repaired config to {}", path.display()));
+        }
+        check_links(&mut report, config);
+        check_shadowed(&mut report, config);
+        check_case_collisions(&mut report, config);
+    }
+    check_opener(&mut report, &config.unwrap_or_default())?;
+    check_completions(&mut report);
+
+    info!();
+    if report.failures > 0 {
+        anyhow::bail!(
+            "{} problem(s) found{}",
+            report.failures,
+            if fix {
+                ""
+            } else {
+                "; run `web doctor --fix` to repair what can be repaired"
+            }
+        );
+    }
+    if report.warnings > 0 {
+        info!("No problems found ({} warning(s)).", report.warnings);
+    } else {
+        info!("No problems found.");
+    }
+    Ok(())
+}
+
+fn check_config_dir(report: &mut Report, config_path: &Path, fix: bool) {
+    let Some(dir) = config_path.parent() else {
+        return;
+    };
+    if !dir.exists() {
+        if fix {
+            match std::fs::create_dir_all(dir) {
+                Ok(()) => report.fixed(format!("Created config directory {}", dir.display())),
+                Err(e) => report.check(
+                    Status::Fail,
+                    format!("Could not create config directory {}: {}", dir.display(), e),
+                ),
+            }
+        } else {
+            report.check(
+                Status::Warn,
+                format!(
+                    "Config directory {} does not exist yet (created on first `web add`)",
+                    dir.display()
+                ),
+            );
+        }
+        return;
+    }
+    let metadata = match std::fs::metadata(dir) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            report.check(
+                Status::Fail,
+                format!("Cannot access {}: {}", dir.display(), e),
+            );
+            return;
+        }
+    };
+    if metadata.permissions().readonly() {
+        report.check(
+            Status::Fail,
+            format!("Config directory {} is read-only", dir.display()),
+        );
+        return;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = metadata.permissions().mode();
+        if mode & 0o022 != 0 {
+            if fix {
+                let repaired = std::fs::Permissions::from_mode(mode & !0o022);
+                match std::fs::set_permissions(dir, repaired) {
+                    Ok(()) => report.fixed(format!(
+                        "Removed group/other write permission from {}",
+                        dir.display()
+                    )),
+                    Err(e) => report.check(
+                        Status::Fail,
+                        format!("Could not change permissions of {}: {}", dir.display(), e),
+                    ),
+                }
+            } else {
+                report.check(
+                    Status::Fail,
+                    format!(
+                        "Config directory {} is writable by other users (mode {:o})",
+                        dir.display(),
+                        mode & 0o777
+                    ),
+                );
+            }
+            return;
+        }
+    }
+    report.check(
+        Status::Ok,
+        format!("Config directory {} is writable", dir.display()),
+    );
+}
+
+fn check_config_file(report: &mut Report, path: &Path) -> Option<Config> {
+    if !path.exists() {
+        report.check(
+            Status::Ok,
+            format!("No config file at {} yet", path.display()),
+        );
+        return None;
+    }
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            report.check(
+                Status::Fail,
+                format!("Cannot read {}: {}", path.display(), e),
+            );
+            return None;
+        }
+    };
+    match toml::from_str::<Config>(&content) {
+        Ok(config) => {
+            report.check(
+                Status::Ok,
+                format!(
+                    "Config file {} parsed ({} alias(es))",
+                    path.display(),
+                    config.aliases.len()
+                ),
+            );
+            Some(config)
+        }
+        Err(e) => {
+            report.check(
+                Status::Fail,
+                format!(
+                    "Config file {} is not valid: {}",
+                    path.display(),
+                    e.message()
+                ),
+            );
+            None
+        }
+    }
+}
+
+/// Flags URLs that can't be opened. Returns whether anything was repaired.
+fn check_urls(report: &mut Report, config: &mut Config, fix: bool) -> bool {
+    let mut repaired = false;
+    let mut problems = 0;
+    for (alias, entry) in config.aliases.iter_mut() {
+        let targets: Vec<&mut String> = if entry.urls.is_empty() {
+            vec![&mut entry.url]
+        } else {
+            entry.urls.iter_mut().collect()
+        };
+        for target in targets {
+            let url = target.trim();
+            // A bare domain such as `example.com/path` only lacks its scheme.
+            let (problem, fixable) = if url.is_empty() {
+                ("is empty", false)
+            } else if url.starts_with('@') {
+                // Links to other aliases are checked by `check_links`.
+                continue;
+            } else if url.chars().any(char::is_whitespace) {
+                ("contains whitespace", false)
+            } else if !template::has_scheme(url) {
+                ("has no scheme (e.g. https://)", url.contains('.'))
+            } else {
+                continue;
+            };
+            if fix && fixable {
+                let new_url = format!("https://{}", url);
+                report.fixed(format!("'{}': {} -> {}", alias, target, new_url));
+                *target = new_url;
+                repaired = true;
+            } else {
+                problems += 1;
+                report.check(
+                    Status::Fail,
+                    format!("Alias '{}' URL {}: {}", alias, problem, target),
+                );
+            }
+        }
+    }
+    if problems == 0 && !config.aliases.is_empty() {
+        report.check(Status::Ok, "All alias URLs look valid");
+    }
+    repaired
+}
+
+/// Follows `@alias` links, flagging links to missing aliases and cycles.
+fn check_links(report: &mut Report, config: &Config) {
+    let links: Vec<&String> = config
+        .aliases
+        .iter()
+        .filter(|(_, entry)| entry.url.starts_with('@'))
+        .map(|(alias, _)| alias)
+        .collect();
+    let mut problems = 0;
+    for alias in &links {
+        if let Err(e) = config::find_alias(config, alias) {
+            problems += 1;
+            report.check(Status::Fail, e.to_string());
+        }
+    }
+    if problems == 0 && !links.is_empty() {
+        report.check(Status::Ok, format!("{} alias link(s) resolve", links.len()));
+    }
+}
+
+/// Flags aliases named like a subcommand, which `web <alias>` doesn't reach.
+fn check_shadowed(report: &mut Report, config: &Config) {
+    for alias in config.aliases.keys().filter(|a| Cli::is_subcommand(a)) {
+        report.check(
+            Status::Warn,
+            format!(
+                "Alias '{}' is shadowed by `web {}`; open it with `web open {}` or rename it",
+                alias, alias, alias
+            ),
+        );
+    }
+}
+
+/// With `ignore_case`, flags aliases that differ only in case, of which only one is reachable.
+fn check_case_collisions(report: &mut Report, config: &Config) {
+    for names in config::case_collisions(config) {
+        report.check(
+            Status::Warn,
+            format!(
+                "Aliases {} differ only in case, and ignore_case is set; rename all but one",
+                names.join(", ")
+            ),
+        );
+    }
+}
+
+fn check_opener(report: &mut Report, config: &Config) -> Result<()> {
+    let (program, found) = browser::default_opener(config)?;
+    if found {
+        report.check(Status::Ok, format!("Default opener `{}` found", program));
+    } else {
+        report.check(
+            Status::Fail,
+            format!("Default opener `{}` not found in PATH", program),
+        );
+    }
+    Ok(())
+}
+
+/// Looks for the completion setups suggested in the README for the current shell.
+fn check_completions(report: &mut Report) {
+    let Some(home) = dirs::home_dir() else {
+        return;
+    };
+    let shell = std::env::var("SHELL").unwrap_or_default();
+    let shell = shell.rsplit('/').next().unwrap_or_default();
+    let (rc_files, script_files): (Vec<_>, Vec<_>) = match shell {
+        "zsh" => (vec![home.join(".zshrc")], vec![home.join(".zfunc/_web")]),
+        "bash" => (
+            vec![home.join(".bashrc"), home.join(".bash_profile")],
+            vec![
+                "/etc/bash_completion.d/web".into(),
+                home.join(".local/share/bash-completion/completions/web"),
+            ],
+        ),
+        "fish" => (
+            vec![home.join(".config/fish/config.fish")],
+            vec![home.join(".config/fish/completions/web.fish")],
+        ),
+        _ => {
+            report.check(
+                Status::Warn,
+                format!("Cannot check shell completions for shell '{}'", shell),
+            );
+            return;
+        }
+    };
+    let in_rc = rc_files.iter().any(|rc| {
+        std::fs::read_to_string(rc).is_ok_and(|content| {
+            content.contains("web completions") || content.contains("COMPLETE=")
+        })
+    });
+    let script = script_files.iter().find(|f| f.exists());
+    if in_rc || script.is_some() {
+        let location = script
+            .map(|f| f.display().to_string())
+            .unwrap_or_else(|| "shell startup file".to_string());
+        report.check(
+            Status::Ok,
+            format!("{} completions installed ({})", shell, location),
+        );
+    } else {
+        report.check(
+            Status::Warn,
+            format!(
+                "{} completions not installed; see `web completions --help`",
+                shell
+            ),
+        );
+    }
+}
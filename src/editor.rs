@@ -0,0 +1,124 @@
+//! `web add --edit`: an alias written in `$EDITOR`, for URLs too unwieldy for a shell line.
+
+use anyhow::{anyhow, Context, Result};
+use std::io::Write;
+
+/// What the buffer holds after the editor is closed.
+pub struct Entry {
+    pub aliases: String,
+    pub url: String,
+    pub tags: Vec<String>,
+    pub description: Option<String>,
+}
+
+/// Open the buffer for `entry` (as far as it is known) in the editor and parse it when the
+/// editor exits. `None` if the alias or the URL was left empty.
+pub fn edit_entry(entry: &Entry) -> Result<Option<Entry>> {
+    let buffer = format!(
+        "alias: {}\nurl: {}\ntags: {}\ndescription: {}\n\n\
+         # Fill in the alias (comma-separated for several) and the URL, and optionally\n\
+         # comma-separated tags and a description. Lines starting with '#' are ignored.\n\
+         # Leaving the alias or the URL empty aborts.\n",
+        entry.aliases,
+        entry.url,
+        entry.tags.join(", "),
+        entry.description.as_deref().unwrap_or_default()
+    );
+    parse(&edit(&buffer)?)
+}
+
+/// Let the user edit `text` in `$VISUAL` or `$EDITOR` (`vi` if neither is set), and return
+/// the result.
+fn edit(text: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    // Created afresh, readable only by the user, and removed when dropped.
+    let mut file = tempfile::Builder::new()
+        .prefix("web-add-")
+        .suffix(".txt")
+        .tempfile()
+        .context("Failed to create a temporary file")?;
+    let path = file.path().to_path_buf();
+    file.write_all(text.as_bytes())
+        .and_then(|()| file.flush())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    // Through the shell, so that editors given with arguments (`code --wait`) work.
+    let status = std::process::Command::new("sh")
+        .args(["-c", &format!("{editor} \"$1\""), "sh"])
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to run editor `{}`", editor));
+    let edited = std::fs::read_to_string(&path);
+    let status = status?;
+    if !status.success() {
+        anyhow::bail!(
+            "Editor `{}` exited with {}; nothing was added",
+            editor,
+            status
+        );
+    }
+    edited.with_context(|| format!("Failed to read {}", path.display()))
+}
+
+fn parse(buffer: &str) -> Result<Option<Entry>> {
+    let mut entry = Entry {
+        aliases: String::new(),
+        url: String::new(),
+        tags: vec![],
+        description: None,
+    };
+    let lines = buffer
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+    for line in lines {
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Expected `key: value`, found '{}'", line))?;
+        let value = value.trim().to_string();
+        match key.trim() {
+            "alias" => entry.aliases = value,
+            "url" => entry.url = value,
+            "tags" => {
+                entry.tags = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect()
+            }
+            "description" => entry.description = Some(value).filter(|d| !d.is_empty()),
+            other => anyhow::bail!(
+                "Unknown field '{}'; expected alias, url, tags or description",
+                other
+            ),
+        }
+    }
+    Ok((!entry.aliases.is_empty() && !entry.url.is_empty()).then_some(entry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_fields_and_skips_comments() {
+        let entry = parse("alias: gh, hub\nurl: https://github.com\ntags: a, , b\n# note\n")
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.aliases, "gh, hub");
+        assert_eq!(entry.url, "https://github.com");
+        assert_eq!(entry.tags, ["a", "b"]);
+        assert_eq!(entry.description, None);
+    }
+
+    #[test]
+    fn parse_aborts_on_empty_fields_and_rejects_unknown_ones() {
+        assert!(parse("alias: gh\nurl:\n").unwrap().is_none());
+        assert!(parse("alias: gh\nbrowser: arc\n").is_err());
+        assert!(parse("just text\n").is_err());
+    }
+}
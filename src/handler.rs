@@ -0,0 +1,156 @@
+//! Registration of `web` as the handler for `web://alias` links.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::browser::{find_in_path, format_command, Platform};
+use crate::output::info;
+
+pub const SCHEME: &str = "web";
+
+/// The alias named by a `web://alias` link, or `None` if `arg` is not such a link.
+pub fn alias_from_url(arg: &str) -> Option<&str> {
+    let rest = arg.strip_prefix(SCHEME)?.strip_prefix(':')?;
+    Some(rest.trim_start_matches('/').trim_end_matches('/'))
+}
+
+/// Register the running binary as the handler for the `web://` scheme.
+pub fn register() -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate the web executable")?;
+    match Platform::detect() {
+        Platform::MacOs => register_macos(&exe),
+        Platform::Linux => register_linux(&exe),
+        Platform::Wsl => bail!(
+            "Registering a URL scheme handler is not supported under WSL; \
+             register it on the Windows side instead"
+        ),
+    }
+}
+
+/// Run `program` and fail with its name if it can't be run or exits unsuccessfully.
+fn run(program: &str, cmd: &mut Command) -> Result<()> {
+    let status = cmd
+        .status()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !status.success() {
+        bail!("{} exited with {}", program, status);
+    }
+    Ok(())
+}
+
+/// Writes a desktop entry for the scheme and makes it the default via `xdg-mime`.
+fn register_linux(exe: &Path) -> Result<()> {
+    let apps = dirs::data_dir()
+        .context("Could not determine the data directory")?
+        .join("applications");
+    std::fs::create_dir_all(&apps)
+        .with_context(|| format!("Failed to create directory {}", apps.display()))?;
+    let file_name = "web-url-handler.desktop";
+    let desktop = apps.join(file_name);
+    let exe = exe.to_string_lossy();
+    // Desktop entries quote arguments with double quotes rather than shell quoting.
+    let exec = format!("\"{}\"", exe.replace('\\', "\\\\").replace('"', "\\\""));
+    let entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=web alias handler\n\
+         Comment=Open web:// alias links\n\
+         Exec={exec} %u\n\
+         Terminal=false\n\
+         NoDisplay=true\n\
+         MimeType=x-scheme-handler/{SCHEME};\n"
+    );
+    std::fs::write(&desktop, entry)
+        .with_context(|| format!("Failed to write {}", desktop.display()))?;
+    info!("Wrote {}", desktop.display());
+
+    if find_in_path("update-desktop-database").is_some() {
+        // Only refreshes the cache; xdg-mime below doesn't depend on it.
+        Command::new("update-desktop-database")
+            .arg(&apps)
+            .status()
+            .ok();
+    }
+    if find_in_path("xdg-mime").is_none() {
+        bail!(
+            "xdg-mime not found; set {} as the default handler for x-scheme-handler/{} manually",
+            file_name,
+            SCHEME
+        );
+    }
+    run(
+        "xdg-mime",
+        Command::new("xdg-mime").args([
+            "default",
+            file_name,
+            &format!("x-scheme-handler/{SCHEME}"),
+        ]),
+    )?;
+    info!("Registered {SCHEME}:// links to open with {exe}");
+    Ok(())
+}
+
+const LSREGISTER: &str = "/System/Library/Frameworks/CoreServices.framework/Frameworks/\
+                          LaunchServices.framework/Support/lsregister";
+
+/// Builds a small AppleScript applet that forwards `web://` URLs to the binary, declares
+/// the scheme in its Info.plist and registers it with Launch Services.
+fn register_macos(exe: &Path) -> Result<()> {
+    let apps: PathBuf = dirs::home_dir()
+        .context("Could not determine home directory")?
+        .join("Applications");
+    std::fs::create_dir_all(&apps)
+        .with_context(|| format!("Failed to create directory {}", apps.display()))?;
+    let app = apps.join("Web URL Handler.app");
+    if app.exists() {
+        std::fs::remove_dir_all(&app)
+            .with_context(|| format!("Failed to replace {}", app.display()))?;
+    }
+
+    let script = format!(
+        "on open location this_URL\n\
+         \tdo shell script {} & \" \" & quoted form of this_URL\n\
+         end open location\n",
+        applescript_string(&format_command(&Command::new(exe)))
+    );
+    run(
+        "osacompile",
+        Command::new("osacompile")
+            .arg("-o")
+            .arg(&app)
+            .args(["-e", &script]),
+    )?;
+
+    let plist = app.join("Contents/Info.plist");
+    let url_types =
+        format!(r#"[{{"CFBundleURLName":"Web alias","CFBundleURLSchemes":["{SCHEME}"]}}]"#);
+    for (key, kind, value) in [
+        (
+            "CFBundleIdentifier",
+            "-string",
+            "io.github.nmz0429.web-url-handler",
+        ),
+        ("LSBackgroundOnly", "-bool", "true"),
+        ("CFBundleURLTypes", "-json", url_types.as_str()),
+    ] {
+        run(
+            "plutil",
+            Command::new("plutil")
+                .args(["-replace", key, kind, value])
+                .arg(&plist),
+        )?;
+    }
+    run("lsregister", Command::new(LSREGISTER).arg("-f").arg(&app))?;
+    info!(
+        "Registered {SCHEME}:// links to open with {} (via {})",
+        exe.display(),
+        app.display()
+    );
+    Ok(())
+}
+
+/// Quote `s` as an AppleScript string literal.
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
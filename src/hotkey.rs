@@ -0,0 +1,356 @@
+//! Global keyboard shortcut that opens the alias picker from anywhere on the desktop.
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+use crate::output::info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// An ASCII letter (lowercase) or digit
+    Char(char),
+    Space,
+    /// Function key F1-F12
+    F(u8),
+}
+
+/// A key combination such as `ctrl+alt+w`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// Command on macOS, Super/Windows key on Linux
+    pub cmd: bool,
+    pub key: Key,
+}
+
+impl FromStr for Hotkey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut ctrl, mut alt, mut shift, mut cmd) = (false, false, false, false);
+        let mut key = None;
+        for part in s.split('+').map(|p| p.trim().to_lowercase()) {
+            match part.as_str() {
+                "ctrl" | "control" => ctrl = true,
+                "alt" | "option" | "opt" => alt = true,
+                "shift" => shift = true,
+                "cmd" | "command" | "super" | "meta" | "win" => cmd = true,
+                _ if key.is_some() => return Err(format!("more than one key in '{}'", s)),
+                "space" => key = Some(Key::Space),
+                k if k.len() == 1 && k.chars().all(|c| c.is_ascii_alphanumeric()) => {
+                    key = k.chars().next().map(Key::Char)
+                }
+                k => match k.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => key = Some(Key::F(n)),
+                    _ => return Err(format!("unknown key '{}'", part)),
+                },
+            }
+        }
+        let key = key.ok_or_else(|| format!("no key in '{}'", s))?;
+        if !(ctrl || alt || cmd) {
+            return Err("a global shortcut needs ctrl, alt or cmd".to_string());
+        }
+        Ok(Hotkey {
+            ctrl,
+            alt,
+            shift,
+            cmd,
+            key,
+        })
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (on, name) in [
+            (self.ctrl, "ctrl+"),
+            (self.alt, "alt+"),
+            (self.shift, "shift+"),
+            (self.cmd, "cmd+"),
+        ] {
+            if on {
+                f.write_str(name)?;
+            }
+        }
+        match self.key {
+            Key::Char(c) => write!(f, "{}", c),
+            Key::Space => f.write_str("space"),
+            Key::F(n) => write!(f, "f{}", n),
+        }
+    }
+}
+
+/// Listen for `hotkey` until interrupted, running `web pick` each time it is pressed.
+///
+/// The picker runs as a separate process so a failure while opening one alias doesn't
+/// stop the daemon, and it is detached from the terminal so it shows a desktop menu.
+pub fn run_daemon(hotkey: Hotkey) -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate the web executable")?;
+    let on_press = move || match Command::new(&exe).arg("pick").stdin(Stdio::null()).spawn() {
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(e) => eprintln!("Error: Failed to run web pick: {}", e),
+    };
+    info!("Press {} to pick an alias (Ctrl-C to stop).", hotkey);
+    listen(hotkey, on_press)
+}
+
+#[cfg(target_os = "macos")]
+fn listen(hotkey: Hotkey, on_press: impl FnMut() + 'static) -> Result<()> {
+    macos::listen(hotkey, Box::new(on_press))
+}
+
+#[cfg(target_os = "linux")]
+fn listen(hotkey: Hotkey, on_press: impl FnMut()) -> Result<()> {
+    if std::env::var_os("DISPLAY").is_none_or(|d| d.is_empty()) {
+        anyhow::bail!(
+            "Global shortcuts need an X11 display. On Wayland, bind `web pick` to a key \
+             in your compositor's settings instead"
+        );
+    }
+    x11::listen(hotkey, on_press)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn listen(_hotkey: Hotkey, _on_press: impl FnMut()) -> Result<()> {
+    anyhow::bail!(
+        "Global shortcuts are not supported on this platform; bind `web pick` to a key instead"
+    )
+}
+
+/// Uses the Carbon hot key API, which unlike an event tap doesn't need the
+/// Accessibility permission.
+#[cfg(target_os = "macos")]
+mod macos {
+    use anyhow::{bail, Result};
+    use std::ffi::c_void;
+
+    use super::{Hotkey, Key};
+
+    type OSStatus = i32;
+    type EventTargetRef = *mut c_void;
+    type EventHandlerRef = *mut c_void;
+    type EventHandlerCallRef = *mut c_void;
+    type EventRef = *mut c_void;
+    type EventHotKeyRef = *mut c_void;
+    type EventHandlerUPP = extern "C" fn(EventHandlerCallRef, EventRef, *mut c_void) -> OSStatus;
+
+    #[repr(C)]
+    struct EventTypeSpec {
+        event_class: u32,
+        event_kind: u32,
+    }
+
+    #[repr(C)]
+    struct EventHotKeyID {
+        signature: u32,
+        id: u32,
+    }
+
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        fn GetApplicationEventTarget() -> EventTargetRef;
+        fn InstallEventHandler(
+            target: EventTargetRef,
+            handler: EventHandlerUPP,
+            num_types: u32,
+            list: *const EventTypeSpec,
+            user_data: *mut c_void,
+            out_ref: *mut EventHandlerRef,
+        ) -> OSStatus;
+        fn RegisterEventHotKey(
+            key_code: u32,
+            modifiers: u32,
+            id: EventHotKeyID,
+            target: EventTargetRef,
+            options: u32,
+            out_ref: *mut EventHotKeyRef,
+        ) -> OSStatus;
+        fn RunApplicationEventLoop();
+    }
+
+    const K_EVENT_CLASS_KEYBOARD: u32 = u32::from_be_bytes(*b"keyb");
+    const K_EVENT_HOT_KEY_PRESSED: u32 = 5;
+    const CMD_KEY: u32 = 1 << 8;
+    const SHIFT_KEY: u32 = 1 << 9;
+    const OPTION_KEY: u32 = 1 << 11;
+    const CONTROL_KEY: u32 = 1 << 12;
+
+    type Callback = Box<dyn FnMut()>;
+
+    extern "C" fn handle(_: EventHandlerCallRef, _: EventRef, user_data: *mut c_void) -> OSStatus {
+        // SAFETY: `user_data` is the leaked callback installed in `listen`.
+        let callback = unsafe { &mut *(user_data as *mut Callback) };
+        callback();
+        0
+    }
+
+    /// Virtual key code (`kVK_*`) of a key on an ANSI keyboard layout.
+    fn key_code(key: Key) -> Option<u32> {
+        const LETTERS: [u32; 26] = [
+            0x00, 0x0B, 0x08, 0x02, 0x0E, 0x03, 0x05, 0x04, 0x22, 0x26, 0x28, 0x25, 0x2E, 0x2D,
+            0x1F, 0x23, 0x0C, 0x0F, 0x01, 0x11, 0x20, 0x09, 0x0D, 0x07, 0x10, 0x06,
+        ];
+        const DIGITS: [u32; 10] = [0x1D, 0x12, 0x13, 0x14, 0x15, 0x17, 0x16, 0x1A, 0x1C, 0x19];
+        const FUNCTION: [u32; 12] = [
+            0x7A, 0x78, 0x63, 0x76, 0x60, 0x61, 0x62, 0x64, 0x65, 0x6D, 0x67, 0x6F,
+        ];
+        match key {
+            Key::Char(c @ 'a'..='z') => Some(LETTERS[(c as u8 - b'a') as usize]),
+            Key::Char(c @ '0'..='9') => Some(DIGITS[(c as u8 - b'0') as usize]),
+            Key::Char(_) => None,
+            Key::Space => Some(0x31),
+            Key::F(n) => FUNCTION.get(usize::from(n).checked_sub(1)?).copied(),
+        }
+    }
+
+    pub fn listen(hotkey: Hotkey, on_press: Callback) -> Result<()> {
+        let Some(code) = key_code(hotkey.key) else {
+            bail!("Unsupported key in {}", hotkey);
+        };
+        let modifiers = [
+            (hotkey.ctrl, CONTROL_KEY),
+            (hotkey.alt, OPTION_KEY),
+            (hotkey.shift, SHIFT_KEY),
+            (hotkey.cmd, CMD_KEY),
+        ]
+        .iter()
+        .filter(|(on, _)| *on)
+        .fold(0, |mask, (_, bit)| mask | bit);
+
+        let user_data = Box::into_raw(Box::new(on_press)) as *mut c_void;
+        let spec = EventTypeSpec {
+            event_class: K_EVENT_CLASS_KEYBOARD,
+            event_kind: K_EVENT_HOT_KEY_PRESSED,
+        };
+        let id = EventHotKeyID {
+            signature: u32::from_be_bytes(*b"webk"),
+            id: 1,
+        };
+        // SAFETY: all pointers outlive the event loop: `spec` is only read during
+        // the call and `user_data` is leaked for the life of the process.
+        unsafe {
+            let target = GetApplicationEventTarget();
+            let mut handler: EventHandlerRef = std::ptr::null_mut();
+            let status = InstallEventHandler(target, handle, 1, &spec, user_data, &mut handler);
+            if status != 0 {
+                bail!(
+                    "Failed to install the hot key handler (OSStatus {})",
+                    status
+                );
+            }
+            let mut hot_key: EventHotKeyRef = std::ptr::null_mut();
+            let status = RegisterEventHotKey(code, modifiers, id, target, 0, &mut hot_key);
+            if status != 0 {
+                bail!(
+                    "Failed to register {} (OSStatus {}); it may already be in use",
+                    hotkey,
+                    status
+                );
+            }
+            RunApplicationEventLoop();
+        }
+        Ok(())
+    }
+}
+
+/// Grabs the key on the X11 root window.
+#[cfg(target_os = "linux")]
+mod x11 {
+    use anyhow::{Context, Result};
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{ConnectionExt, GrabMode, ModMask};
+    use x11rb::protocol::Event;
+
+    use super::{Hotkey, Key};
+
+    fn keysym(key: Key) -> u32 {
+        match key {
+            // Latin-1 keysyms equal their character codes.
+            Key::Char(c) => c as u32,
+            Key::Space => 0x20,
+            Key::F(n) => 0xffbe + u32::from(n) - 1,
+        }
+    }
+
+    pub fn listen(hotkey: Hotkey, mut on_press: impl FnMut()) -> Result<()> {
+        let (conn, screen) = x11rb::connect(None).context("Failed to connect to the X server")?;
+        let setup = conn.setup();
+        let root = setup.roots[screen].root;
+
+        let (min, max) = (setup.min_keycode, setup.max_keycode);
+        let mapping = conn.get_keyboard_mapping(min, max - min + 1)?.reply()?;
+        let per_keycode = usize::from(mapping.keysyms_per_keycode);
+        let wanted = keysym(hotkey.key);
+        let keycode = mapping
+            .keysyms
+            .chunks(per_keycode)
+            .position(|syms| syms.contains(&wanted))
+            .map(|i| min + i as u8)
+            .with_context(|| format!("No key on this keyboard produces {}", hotkey))?;
+
+        let mut modifiers = ModMask::from(0u16);
+        for (on, mask) in [
+            (hotkey.ctrl, ModMask::CONTROL),
+            (hotkey.alt, ModMask::M1),
+            (hotkey.shift, ModMask::SHIFT),
+            (hotkey.cmd, ModMask::M4),
+        ] {
+            if on {
+                modifiers |= mask;
+            }
+        }
+        // Grab with every combination of Caps Lock and Num Lock so they don't block the shortcut.
+        for locks in [
+            ModMask::from(0u16),
+            ModMask::LOCK,
+            ModMask::M2,
+            ModMask::LOCK | ModMask::M2,
+        ] {
+            conn.grab_key(
+                true,
+                root,
+                modifiers | locks,
+                keycode,
+                GrabMode::ASYNC,
+                GrabMode::ASYNC,
+            )?
+            .check()
+            .with_context(|| format!("Failed to grab {}; it may already be in use", hotkey))?;
+        }
+        conn.flush()?;
+
+        loop {
+            if let Event::KeyPress(_) = conn.wait_for_event()? {
+                on_press();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hotkey_parses_and_round_trips() {
+        let hotkey: Hotkey = "Ctrl + Alt + W".parse().unwrap();
+        assert_eq!(hotkey.key, Key::Char('w'));
+        assert!(hotkey.ctrl && hotkey.alt && !hotkey.shift && !hotkey.cmd);
+        assert_eq!(hotkey.to_string().parse::<Hotkey>(), Ok(hotkey));
+        assert_eq!("cmd+f12".parse::<Hotkey>().map(|h| h.key), Ok(Key::F(12)));
+    }
+
+    #[test]
+    fn hotkey_rejects_incomplete_combinations() {
+        assert!("shift+w".parse::<Hotkey>().is_err());
+        assert!("ctrl+a+b".parse::<Hotkey>().is_err());
+        assert!("ctrl+f13".parse::<Hotkey>().is_err());
+        assert!("ctrl+alt".parse::<Hotkey>().is_err());
+    }
+}
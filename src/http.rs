@@ -0,0 +1,132 @@
+//! HTTP requests made through `curl`, which is available on every supported platform.
+
+use anyhow::{bail, Context, Result};
+use std::process::{Command, Stdio};
+
+use crate::browser::find_in_path;
+
+/// Outcome of a request after following redirects.
+pub struct Response {
+    pub status: u16,
+    /// The URL the last redirect led to
+    pub final_url: String,
+}
+
+fn request(url: &str, method_args: &[&str], timeout_secs: u32) -> Result<Response> {
+    if find_in_path("curl").is_none() {
+        bail!("curl not found; it is needed to check URLs");
+    }
+    let output = Command::new("curl")
+        .args(["--silent", "--show-error", "--location", "--max-time"])
+        .arg(timeout_secs.to_string())
+        .args(method_args)
+        .args([
+            "--output",
+            "/dev/null",
+            "--write-out",
+            "%{http_code} %{url_effective}",
+        ])
+        .arg(url)
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run curl")?;
+    if !output.status.success() {
+        bail!(
+            "Request to {} failed: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let out = String::from_utf8_lossy(&output.stdout);
+    let (status, final_url) = out
+        .split_once(' ')
+        .with_context(|| format!("Unexpected output from curl: {}", out))?;
+    Ok(Response {
+        status: status.parse().context("Unexpected status code from curl")?,
+        final_url: final_url.to_string(),
+    })
+}
+
+/// Send a HEAD request to `url`, following redirects. Servers that don't support HEAD are
+/// sent a GET instead.
+pub fn head(url: &str) -> Result<Response> {
+    let response = request(url, &["--head"], 15)?;
+    if matches!(response.status, 405 | 501) {
+        return get(url, 15);
+    }
+    Ok(response)
+}
+
+/// Send a GET request to `url`, following redirects, and discard the body.
+pub fn get(url: &str, timeout_secs: u32) -> Result<Response> {
+    request(url, &[], timeout_secs)
+}
+
+/// Short description of an HTTP status code.
+pub fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201..=299 => "Success",
+        301 | 302 | 303 | 307 | 308 => "Redirect",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        400..=499 => "Client Error",
+        500..=599 => "Server Error",
+        _ => "",
+    }
+}
+
+/// The `<title>` of the HTML page at `url`, if it has one.
+pub fn title(url: &str) -> Result<Option<String>> {
+    if find_in_path("curl").is_none() {
+        bail!("curl not found; it is needed to fetch page titles");
+    }
+    let output = Command::new("curl")
+        .args(["--silent", "--show-error", "--location", "--max-time", "10"])
+        .arg(url)
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run curl")?;
+    if !output.status.success() {
+        bail!(
+            "Request to {} failed: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(page_title(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// The text of the `<title>` element in `html`, with entities decoded and whitespace
+/// collapsed.
+fn page_title(html: &str) -> Option<String> {
+    // Byte offsets found in the lowercased copy hold in `html`, as ASCII case folding
+    // keeps lengths.
+    let lower = html.to_ascii_lowercase();
+    let start = lower.find("<title")?;
+    let open_end = lower[start..].find('>').map(|i| start + i + 1)?;
+    let close = lower[open_end..].find("</title").map(|i| open_end + i)?;
+    // `&amp;` last, so that an escaped entity such as `&amp;lt;` stays `&lt;`.
+    let title = html[open_end..close]
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&");
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_title_decodes_entities_once() {
+        let html = "<html><head><TITLE lang=en>\n  Fish &amp; Chips &amp;lt;3\n</TITLE></head>";
+        assert_eq!(page_title(html).as_deref(), Some("Fish & Chips &lt;3"));
+        assert_eq!(page_title("<title> </title>"), None);
+        assert_eq!(page_title("<p>no title</p>"), None);
+    }
+}
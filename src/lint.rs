@@ -0,0 +1,215 @@
+//! `web lint`: mistakes in a config file that parses, reported as text or as JSON for
+//! scripts such as pre-commit hooks.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::browser;
+use crate::cli::Cli;
+use crate::config::{self, Alias, Config};
+use crate::output::info;
+use crate::template;
+
+/// One problem: which check found it, where in the config, and what is wrong.
+#[derive(Debug, Serialize)]
+struct Finding {
+    check: &'static str,
+    /// Dotted key of the setting, e.g. `aliases.gh` or `groups.dev`
+    location: String,
+    message: String,
+}
+
+#[derive(Default)]
+struct Findings(Vec<Finding>);
+
+impl Findings {
+    fn add(&mut self, check: &'static str, location: &str, message: String) {
+        self.0.push(Finding {
+            check,
+            location: location.to_string(),
+            message,
+        });
+    }
+}
+
+/// Check the config file at `path` (the usual one if not given). Fails if anything was found.
+pub fn run(path: Option<&Path>, json: bool) -> Result<()> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => config::config_path()?,
+    };
+    if !path.exists() {
+        anyhow::bail!("No config file at {}", path.display());
+    }
+    let config = config::load_from(&path)?;
+    let findings = lint(&config).0;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&findings)?);
+    } else {
+        for finding in &findings {
+            println!(
+                "{}: {}: {} [{}]",
+                path.display(),
+                finding.location,
+                finding.message,
+                finding.check
+            );
+        }
+    }
+    if !findings.is_empty() {
+        anyhow::bail!("{} problem(s) found in {}", findings.len(), path.display());
+    }
+    if !json {
+        info!("No problems found in {}.", path.display());
+    }
+    Ok(())
+}
+
+fn lint(config: &Config) -> Findings {
+    let mut findings = Findings::default();
+    let tables = std::iter::once(("aliases".to_string(), &config.aliases)).chain(
+        config
+            .overrides
+            .iter()
+            .map(|(host, aliases)| (format!("overrides.{}", host), &aliases.0)),
+    );
+    for (table, aliases) in tables {
+        for (alias, entry) in aliases {
+            let location = format!("{}.{}", table, alias);
+            check_alias(&mut findings, config, &location, alias, entry);
+        }
+    }
+    for names in config::duplicate_aliases(config) {
+        let url = config.aliases[&names[0]].display_url();
+        findings.add(
+            "duplicate-target",
+            &format!("aliases.{}", names[0]),
+            format!(
+                "{} all open {}; merge them with `web dedupe`",
+                names.join(", "),
+                url
+            ),
+        );
+    }
+    if let Some(name) = &config.default_browser {
+        check_browser(&mut findings, config, "default_browser", name);
+    }
+    for (group, members) in &config.groups {
+        let location = format!("groups.{}", group);
+        if members.is_empty() {
+            findings.add("empty-group", &location, "has no aliases".to_string());
+        }
+        for member in members.iter().filter(|m| !config.aliases.contains_key(*m)) {
+            findings.add(
+                "unknown-alias",
+                &location,
+                format!("includes '{}', which is not an alias", member),
+            );
+        }
+    }
+    findings
+}
+
+fn check_alias(
+    findings: &mut Findings,
+    config: &Config,
+    location: &str,
+    alias: &str,
+    entry: &Alias,
+) {
+    if Cli::is_subcommand(alias) {
+        findings.add(
+            "shadowed-alias",
+            location,
+            format!(
+                "`web {}` runs the subcommand; only `web open {}` opens it",
+                alias, alias
+            ),
+        );
+    }
+    if let Some(name) = &entry.browser {
+        check_browser(findings, config, location, name);
+    }
+    if let Some(target) = entry.url.strip_prefix('@') {
+        let exists = config.aliases.contains_key(target)
+            || config.overrides.values().any(|h| h.0.contains_key(target));
+        if !exists {
+            findings.add(
+                "broken-link",
+                location,
+                format!("points to '@{}', which is not an alias", target),
+            );
+        } else if location.starts_with("aliases.") {
+            // Cycles and overlong chains of links.
+            if let Err(e) = config::find_alias(config, alias) {
+                findings.add("broken-link", location, e.to_string());
+            }
+        }
+        return;
+    }
+    if entry.url.is_empty() && entry.urls.is_empty() {
+        findings.add("malformed-url", location, "has no URL".to_string());
+    }
+    let urls = std::iter::once(&entry.url)
+        .filter(|url| !url.is_empty())
+        .chain(&entry.urls)
+        .chain(entry.locales.values());
+    for url in urls {
+        // Wildcard aliases put what `*` matched into the URL.
+        let sample = if alias.contains('*') {
+            url.replace('*', "x")
+        } else {
+            url.clone()
+        };
+        if let Err(e) = template::validate(&sample) {
+            findings.add("malformed-url", location, e.to_string());
+        }
+        for inner in template::placeholders(url) {
+            if let Some(problem) = placeholder_problem(config, &inner) {
+                findings.add("unreachable-placeholder", location, problem);
+            }
+        }
+    }
+}
+
+/// Why `{inner}` can never be filled in, if it can't.
+fn placeholder_problem(config: &Config, inner: &str) -> Option<String> {
+    if let Some(name) = inner.strip_prefix("vars.") {
+        return (!config.vars.contains_key(name))
+            .then(|| format!("uses {{{}}}, but [vars] has no '{}'", inner, name));
+    }
+    if inner.starts_with("cmd:") {
+        return (!config.allow_exec)
+            .then(|| format!("runs {{{}}}, but allow_exec is not set", inner));
+    }
+    // Braces around anything but a name, such as JSON, are kept in the URL on purpose.
+    let looks_like_placeholder = !inner.is_empty()
+        && inner
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-.:".contains(c));
+    let known =
+        template::is_argument_placeholder(inner) || template::is_computed_placeholder(inner);
+    (looks_like_placeholder && !known).then(|| {
+        format!(
+            "{{{}}} is not a placeholder and is left in the URL as is",
+            inner
+        )
+    })
+}
+
+fn check_browser(findings: &mut Findings, config: &Config, location: &str, name: &str) {
+    let known = name.eq_ignore_ascii_case("default")
+        || browser::builtin_browser(name).is_some()
+        || config.browsers.contains_key(name);
+    if !known {
+        findings.add(
+            "unknown-browser",
+            location,
+            format!(
+                "browser '{}' is neither built in nor defined under [browsers]",
+                name
+            ),
+        );
+    }
+}
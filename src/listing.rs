@@ -0,0 +1,164 @@
+//! `web list --format`: the aliases as JSON, TSV, CSV or plain lines, for jq, fzf,
+//! spreadsheets and `web add --stdin`; and `web list --group-by`, the aliases under headers.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::cli::{GroupBy, ListFormat};
+use crate::config::Alias;
+use crate::output::{self, Style};
+
+const COLUMNS: [&str; 5] = ["alias", "url", "tags", "description", "pinned"];
+
+/// One alias as a row of output. Fields follow `COLUMNS`.
+#[derive(Serialize)]
+struct Row {
+    alias: String,
+    url: String,
+    tags: Vec<String>,
+    description: Option<String>,
+    pinned: bool,
+}
+
+impl Row {
+    fn fields(&self) -> [String; 5] {
+        [
+            self.alias.clone(),
+            self.url.clone(),
+            self.tags.join(","),
+            self.description.clone().unwrap_or_default(),
+            self.pinned.to_string(),
+        ]
+    }
+}
+
+/// `aliases` in `format`. Even with no aliases, JSON gives `[]` and TSV and CSV their header
+/// row. Plain lines leave out aliases with several URLs, which `web add --stdin` can't read
+/// back.
+pub fn render(mut aliases: BTreeMap<String, Alias>, format: ListFormat) -> Result<String> {
+    if matches!(format, ListFormat::Plain) {
+        aliases.retain(|alias, entry| {
+            if entry.urls.is_empty() {
+                return true;
+            }
+            if !output::quiet() {
+                eprintln!(
+                    "Warning: skipped '{}': it has several URLs; use `web export` for a full copy",
+                    alias
+                );
+            }
+            false
+        });
+    }
+    let rows: Vec<Row> = aliases
+        .into_iter()
+        .map(|(alias, entry)| Row {
+            url: entry.display_url(),
+            alias,
+            tags: entry.tags,
+            description: entry.description,
+            pinned: entry.pinned,
+        })
+        .collect();
+    let mut out = String::new();
+    match format {
+        ListFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(&rows)?)?,
+        ListFormat::Tsv => {
+            writeln!(out, "{}", COLUMNS.join("\t"))?;
+            for row in &rows {
+                let fields = row.fields().map(|f| f.replace(['\t', '\n', '\r'], " "));
+                writeln!(out, "{}", fields.join("\t"))?;
+            }
+        }
+        ListFormat::Csv => {
+            writeln!(out, "{}", COLUMNS.join(","))?;
+            for row in &rows {
+                let fields = row.fields().map(|f| csv_field(&f));
+                writeln!(out, "{}", fields.join(","))?;
+            }
+        }
+        ListFormat::Plain => {
+            for row in &rows {
+                let mut words = vec![row.alias.as_str(), row.url.as_str()];
+                words.extend(row.tags.iter().map(String::as_str));
+                writeln!(out, "{}", words.join(" "))?;
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// `aliases` clustered by `key`, largest cluster first, each under a header with its count.
+/// Text matching `highlight` is highlighted.
+pub fn render_groups(
+    aliases: Vec<(String, Alias)>,
+    key: GroupBy,
+    highlight: Option<&str>,
+) -> String {
+    let mut groups: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+    for (alias, entry) in aliases {
+        let header = match key {
+            GroupBy::Domain => domain(&entry),
+        };
+        groups
+            .entry(header)
+            .or_default()
+            .push((alias, entry.display_url()));
+    }
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    // Stable, so groups of the same size stay in alphabetical order.
+    groups.sort_by_key(|(_, members)| std::cmp::Reverse(members.len()));
+    let mut out = String::new();
+    for (header, members) in groups {
+        out.push_str(&format!("{} ({})\n", header, members.len()));
+        let width = members.iter().map(|(a, _)| a.len()).max().unwrap_or(0);
+        for (alias, url) in members {
+            let padding = " ".repeat(width - alias.len());
+            out.push_str(&format!(
+                "  {}{padding}  {}\n",
+                output::paint(&alias, Style::Alias, highlight),
+                output::paint(&url, Style::Url, highlight)
+            ));
+        }
+    }
+    out
+}
+
+/// The host `entry` opens (its first URL's, for several), without `www.`. Links to other
+/// aliases and URLs without a host are put together under a description instead.
+fn domain(entry: &Alias) -> String {
+    let url = entry.urls.first().unwrap_or(&entry.url);
+    if url.starts_with('@') {
+        return "(links to aliases)".to_string();
+    }
+    match url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(String::from))
+    {
+        Some(host) => host.strip_prefix("www.").unwrap_or(&host).to_string(),
+        None => "(no host)".to_string(),
+    }
+}
+
+/// `field` quoted as CSV requires (RFC 4180) if it contains a comma, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -1,11 +1,40 @@
+mod batch;
 mod browser;
+mod cdp;
 mod cli;
+mod clipboard;
 mod config;
+mod doctor;
+mod editor;
+mod handler;
+mod hotkey;
+mod http;
+mod lint;
+mod listing;
+mod output;
+mod picker;
+mod qr;
+mod recent;
+mod search;
+mod secret;
+mod session;
+mod shortcuts;
+mod state;
+mod template;
+mod trace;
+mod tree;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{CommandFactory, Parser};
 use clap_complete::CompleteEnv;
-use cli::{Cli, Commands};
+use cli::{
+    BrowserChoice, Cli, Commands, GroupCommand, SecretCommand, SessionCommand, TagCommand,
+    TrashCommand, UrlAction,
+};
+use output::{info, Style};
+use std::io::IsTerminal;
+use std::time::Duration;
+use trace::trace;
 
 fn main() {
     CompleteEnv::with_factory(Cli::command).complete();
@@ -15,20 +44,298 @@ fn main() {
     }
 }
 
+/// Opening more aliases than this at once by tag asks for confirmation first.
+const CONFIRM_OPEN_THRESHOLD: usize = 10;
+
 fn run() -> Result<()> {
     let cli = Cli::parse();
+    if cli.verbose {
+        trace::enable();
+    }
+    if cli.quiet {
+        output::set_quiet();
+    }
+    if cli.no_color {
+        output::set_no_color();
+    }
+    if cli.no_pager {
+        output::set_no_pager();
+    }
     match cli.command {
-        Some(Commands::Add { aliases, url }) => {
+        Some(Commands::Add {
+            aliases,
+            url,
+            clipboard,
+            stdin,
+            edit,
+            browser,
+            profile,
+            app,
+            confirm,
+            no_verify,
+            check,
+            expand,
+            append_path,
+            tags,
+            desc,
+            pin,
+        }) => {
+            if stdin {
+                let template = config::Alias {
+                    browser,
+                    profile,
+                    app,
+                    confirm,
+                    append_path,
+                    tags,
+                    description: desc.filter(|d| !d.trim().is_empty()),
+                    pinned: pin,
+                    ..Default::default()
+                };
+                return add_from_stdin(&template, no_verify, expand, cli.force);
+            }
+            let (aliases, url, tags, desc) = if edit {
+                if cli.no_input || !std::io::stdin().is_terminal() {
+                    anyhow::bail!("`web add --edit` must be run in a terminal");
+                }
+                let given = editor::Entry {
+                    aliases: aliases.unwrap_or_default(),
+                    url: url.unwrap_or_default(),
+                    tags,
+                    description: desc,
+                };
+                let Some(entry) = editor::edit_entry(&given)? else {
+                    info!("No alias or URL given; nothing was added");
+                    return Ok(());
+                };
+                (
+                    entry.aliases,
+                    Some(entry.url),
+                    entry.tags,
+                    entry.description,
+                )
+            } else {
+                let aliases =
+                    aliases.expect("clap requires aliases unless --stdin or --edit is given");
+                (aliases, url, tags, desc)
+            };
+            let interactive = !cli.no_input && std::io::stdin().is_terminal();
             let names = config::parse_aliases(&aliases);
-            config::add_alias(&aliases, &url)?;
+            for name in &names {
+                check_alias_name(name)?;
+            }
+            let url = match url {
+                Some(url) => url,
+                None if clipboard => match url_from_clipboard(&aliases, cli.yes, interactive)? {
+                    Some(url) => url,
+                    None => return Ok(()),
+                },
+                None => unreachable!("clap requires a URL unless --clipboard is given"),
+            };
+            let config = config::load()?;
+            let url = if let Some(target) = url.strip_prefix('@') {
+                // A link to another alias, which must exist.
+                if names.contains(&target) {
+                    anyhow::bail!("Alias '{}' can't point to itself", target);
+                }
+                config::resolve_alias(&config, target)?;
+                url
+            } else {
+                if !no_verify {
+                    template::validate(&url)?;
+                }
+                if !cli.force {
+                    browser::check_scheme(&config, &url)?;
+                }
+                let url = if expand { expand_url(url)? } else { url };
+                if check {
+                    check_url(url, interactive)?
+                } else {
+                    url
+                }
+            };
+            let mut entry = config::Alias {
+                url,
+                browser,
+                profile,
+                app,
+                confirm,
+                append_path,
+                tags,
+                description: desc.filter(|d| !d.trim().is_empty()),
+                pinned: pin,
+                ..Default::default()
+            };
             let quoted: Vec<String> = names.iter().map(|a| format!("'{a}'")).collect();
-            println!("Added {} -> {url}", quoted.join(", "));
+            let changed: Vec<(&str, &str)> = names
+                .iter()
+                .filter_map(|name| {
+                    let old = config.aliases.get(*name)?;
+                    (old.url != entry.url).then_some((*name, old.url.as_str()))
+                })
+                .collect();
+            if !changed.is_empty() && !cli.force {
+                if !interactive {
+                    let (name, old) = changed[0];
+                    anyhow::bail!(
+                        "Alias '{}' already exists ({}); pass --force to overwrite it",
+                        name,
+                        old
+                    );
+                }
+                for (name, old) in &changed {
+                    eprintln!(
+                        "'{name}' already exists:\n  old: {old}\n  new: {}",
+                        entry.url
+                    );
+                }
+                if !dialoguer::Confirm::new()
+                    .with_prompt("Overwrite?")
+                    .default(false)
+                    .interact()?
+                {
+                    return Ok(());
+                }
+            }
+            // A link already names its target; offering to link to another link to the
+            // same target would only add a hop.
+            let duplicates: Vec<String> = if entry.url.starts_with('@') {
+                vec![]
+            } else {
+                config::aliases_for_url(&config, &entry.url)
+                    .into_iter()
+                    .filter(|a| !names.contains(&a.as_str()))
+                    .collect()
+            };
+            if !duplicates.is_empty() {
+                if !cli.quiet {
+                    eprintln!(
+                        "Warning: {} is already registered as {}",
+                        entry.url,
+                        duplicates
+                            .iter()
+                            .map(|a| format!("'{a}'"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                }
+                // Only an alias with the one URL can stand in for this one.
+                let single = duplicates
+                    .iter()
+                    .find(|a| config.aliases[a.as_str()].urls.is_empty());
+                if let Some(existing) = single.filter(|_| interactive) {
+                    let prompt = format!(
+                        "Make {} an additional alias for '{existing}'?",
+                        quoted.join(", ")
+                    );
+                    if dialoguer::Confirm::new()
+                        .with_prompt(prompt)
+                        .default(true)
+                        .interact()?
+                    {
+                        // Only the URL becomes a link; the settings given here still apply.
+                        entry.url = format!("@{existing}");
+                    }
+                }
+            }
+            config::add_alias(&aliases, &entry)?;
+            // Aliases that only get new settings keep their history.
+            let added: Vec<&str> = names
+                .iter()
+                .copied()
+                .filter(|name| {
+                    !config.aliases.contains_key(*name) || changed.iter().any(|(n, _)| n == name)
+                })
+                .collect();
+            state::record_added(&added)?;
+            info!("Added {} -> {}", quoted.join(", "), entry.url);
         }
-        Some(Commands::Remove { aliases }) => {
-            let names = config::parse_aliases(&aliases);
-            config::remove_alias(&aliases)?;
+        Some(Commands::Remove { all: true, .. }) => {
+            let count = config::load()?.aliases.len();
+            if count == 0 {
+                info!("No aliases registered.");
+                return Ok(());
+            }
+            if cli.no_input || !std::io::stdin().is_terminal() {
+                anyhow::bail!("Removing all aliases must be confirmed in a terminal");
+            }
+            let typed: String = dialoguer::Input::new()
+                .with_prompt(format!("Type {count} to remove all {count} aliases"))
+                .interact_text()?;
+            if typed.trim() != count.to_string() {
+                anyhow::bail!("Not confirmed; no aliases were removed");
+            }
+            let backup = config::backup()?;
+            info!("Backed up the config to {}", backup.display());
+            config::remove_all_aliases()?;
+            info!("Moved all {count} aliases to the trash");
+        }
+        Some(Commands::Remove { aliases, .. }) => {
+            let aliases = aliases.unwrap_or_default();
+            let config = config::load()?;
+            let names = config::match_aliases(&config, &aliases)?;
             let quoted: Vec<String> = names.iter().map(|a| format!("'{a}'")).collect();
-            println!("Removed {}", quoted.join(", "));
+            if !cli.yes {
+                let listing: Vec<String> = names
+                    .iter()
+                    .map(|name| format!("  '{name}' -> {}", config.aliases[name].display_url()))
+                    .collect();
+                let prompt = format!("Remove {} alias(es)?", names.len());
+                if cli.no_input || !std::io::stdin().is_terminal() {
+                    anyhow::bail!("{} Pass --yes to confirm:\n{}", prompt, listing.join("\n"));
+                }
+                println!("{}", listing.join("\n"));
+                if !dialoguer::Confirm::new()
+                    .with_prompt(prompt)
+                    .default(false)
+                    .interact()?
+                {
+                    return Ok(());
+                }
+            }
+            config::remove_alias(&names)?;
+            info!(
+                "Moved {} to the trash; bring back with `web restore <alias>`",
+                quoted.join(", ")
+            );
+        }
+        Some(Commands::Restore { alias }) => {
+            config::restore_alias(&alias, cli.force)?;
+            info!("Restored '{alias}'");
+        }
+        Some(Commands::Trash(TrashCommand::List)) => {
+            let trash = config::list_trash()?;
+            if trash.is_empty() {
+                info!("The trash is empty.");
+            }
+            let max_len = trash.keys().map(|a| a.len()).max().unwrap_or(0);
+            for (alias, trashed) in trash {
+                let deleted = format_time(&trashed.deleted);
+                let url = trashed.alias.display_url();
+                println!("{:<width$}  {}  {}", alias, deleted, url, width = max_len);
+            }
+        }
+        Some(Commands::Trash(TrashCommand::Empty)) => {
+            let count = config::list_trash()?.len();
+            if count == 0 {
+                info!("The trash is empty.");
+                return Ok(());
+            }
+            if !cli.yes {
+                let prompt = format!("Delete {count} removed alias(es) for good?");
+                if cli.no_input || !std::io::stdin().is_terminal() {
+                    anyhow::bail!("{} Pass --yes to confirm.", prompt);
+                }
+                if !dialoguer::Confirm::new()
+                    .with_prompt(prompt)
+                    .default(false)
+                    .interact()?
+                {
+                    return Ok(());
+                }
+            }
+            config::empty_trash()?;
+            info!("Deleted {count} alias(es) from the trash");
         }
         Some(Commands::Completions { shell }) => {
             if shell == clap_complete::Shell::Zsh {
@@ -46,55 +353,1285 @@ fn run() -> Result<()> {
                     .try_complete(["web"], None::<&std::path::Path>)?;
             }
         }
-        Some(Commands::Export) => {
-            let config = config::load()?;
-            print!("{}", toml::to_string_pretty(&config)?);
+        Some(Commands::Export { shortcuts, tag }) => {
+            let mut config = config::load()?;
+            if let Some(tag) = tag {
+                config.aliases.retain(|_, entry| entry.tags.contains(&tag));
+            }
+            match shortcuts {
+                Some(dir) => shortcuts::export(&config, &dir)?,
+                None => print!("{}", toml::to_string_pretty(&config)?),
+            }
         }
         Some(Commands::Import { path }) => {
             config::import_aliases(&path)?;
         }
         Some(Commands::CompleteAliases) => {
-            let aliases = config::list_aliases()?;
-            for (alias, url) in aliases {
+            let aliases = config::pinned_first(config::list_aliases(None)?);
+            for (alias, entry) in aliases {
+                let description = entry
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| entry.display_url());
                 // Escape colons and backslashes for zsh _describe format
                 let alias = alias.replace('\\', "\\\\").replace(':', "\\:");
-                let url = url.replace('\\', "\\\\");
-                println!("{alias}:{url}");
+                let description = description.replace('\\', "\\\\");
+                println!("{alias}:{description}");
             }
         }
-        Some(Commands::List) => {
-            let aliases = config::list_aliases()?;
-            if aliases.is_empty() {
-                println!("No aliases registered.");
+        Some(Commands::CompleteBrowsers) => {
+            let mut browsers = browser::available_browsers(&config::load()?)?;
+            browsers.sort_by_key(|b| !b.installed);
+            println!("default:system default browser");
+            for b in browsers {
+                let status = if b.installed {
+                    "installed"
+                } else {
+                    "not installed"
+                };
+                println!("{}:{}", b.name.replace(':', "\\:"), status);
+            }
+        }
+        Some(Commands::Browsers { all }) => {
+            let browsers: Vec<_> = browser::available_browsers(&config::load()?)?
+                .into_iter()
+                .filter(|b| all || b.installed)
+                .collect();
+            if browsers.is_empty() {
+                info!("No supported browsers found. Use `web browsers --all` to see all.");
+            }
+            let max_len = browsers.iter().map(|b| b.name.len()).max().unwrap_or(0);
+            for b in browsers {
+                let status = if !all {
+                    ""
+                } else if b.installed {
+                    "installed      "
+                } else {
+                    "not installed  "
+                };
+                let command = b.command.unwrap_or_else(|e| format!("({e})"));
+                println!("{:<width$}  {}{}", b.name, status, command, width = max_len);
+            }
+        }
+        Some(Commands::Doctor { fix }) => {
+            doctor::run(fix)?;
+        }
+        Some(Commands::Lint { path, json }) => {
+            lint::run(path.as_deref(), json)?;
+        }
+        Some(Commands::RegisterHandler) => {
+            handler::register()?;
+        }
+        Some(Commands::Pick) => {
+            let config = config::load()?;
+            pick_and_open(&cli, &config, config::all_aliases(&config), &[])?;
+        }
+        Some(Commands::HotkeyDaemon { key }) => {
+            hotkey::run_daemon(key)?;
+        }
+        Some(Commands::List {
+            pattern,
+            tag,
+            long,
+            format,
+            group_by,
+        }) => {
+            let mut aliases = config::list_aliases(tag.as_deref())?;
+            if let Some(pattern) = &pattern {
+                aliases.retain(|alias, entry| config::matches_pattern(pattern, alias, entry));
+            }
+            // Text (not a glob) is highlighted where it was found.
+            let highlight = pattern.as_deref().filter(|p| !p.contains(['*', '?']));
+            if let Some(format) = format {
+                output::page(&listing::render(aliases, format)?);
+            } else if aliases.is_empty() {
+                match (&pattern, tag) {
+                    (Some(pattern), _) => info!("No aliases match '{pattern}'."),
+                    (None, Some(tag)) => info!("No aliases tagged '{tag}'."),
+                    (None, None) => info!("No aliases registered."),
+                }
+            } else if let Some(key) = group_by {
+                let aliases = config::pinned_first(aliases);
+                output::page(&listing::render_groups(aliases, key, highlight));
+            } else if long {
+                output::page(&format_long(config::pinned_first(aliases), highlight));
             } else {
-                // Group aliases by URL
-                let mut by_url: std::collections::BTreeMap<String, Vec<String>> =
+                // Group aliases by URL, pinned ones first
+                let mut by_url: std::collections::BTreeMap<(bool, String), Vec<String>> =
                     std::collections::BTreeMap::new();
-                for (alias, url) in aliases {
-                    by_url.entry(url).or_default().push(alias);
+                for (alias, entry) in aliases {
+                    let key = (!entry.pinned, entry.display_url());
+                    by_url.entry(key).or_default().push(alias);
                 }
-                let rows: Vec<(String, String)> = by_url
+                let rows: Vec<(Vec<String>, String)> = by_url
                     .into_iter()
-                    .map(|(url, names)| (names.join(", "), url))
+                    .map(|((_, url), names)| (names, url))
                     .collect();
-                let max_len = rows.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+                let width = |names: &[String]| names.iter().map(|n| n.len() + 2).sum::<usize>() - 2;
+                let max_len = rows
+                    .iter()
+                    .map(|(names, _)| width(names))
+                    .max()
+                    .unwrap_or(0);
+                let mut out = String::new();
                 for (names, url) in rows {
-                    println!("{:<width$}  {}", names, url, width = max_len);
+                    let painted: Vec<String> = names
+                        .iter()
+                        .map(|n| output::paint(n, Style::Alias, highlight))
+                        .collect();
+                    let padding = " ".repeat(max_len - width(&names));
+                    let url = output::paint(&url, Style::Url, highlight);
+                    out.push_str(&format!("{}{padding}  {url}\n", painted.join(", ")));
+                }
+                output::page(&out);
+            }
+        }
+        Some(Commands::Tree { namespace, tag }) => {
+            let mut aliases = config::list_aliases(tag.as_deref())?;
+            let root = match namespace {
+                Some(namespace) => {
+                    let namespace = format!("{}/", namespace.trim_end_matches('/'));
+                    aliases = aliases
+                        .into_iter()
+                        .filter_map(|(alias, entry)| {
+                            Some((alias.strip_prefix(&namespace)?.to_string(), entry))
+                        })
+                        .collect();
+                    if aliases.is_empty() {
+                        anyhow::bail!("No aliases in namespace '{}'", namespace);
+                    }
+                    namespace
+                }
+                None => ".".to_string(),
+            };
+            print!("{}", tree::render(&root, config::pinned_first(aliases)));
+        }
+        Some(Commands::Search {
+            ref query,
+            open,
+            limit,
+        }) => {
+            let config = config::load()?;
+            let mut matches = search::rank(config::all_aliases(&config), query);
+            if matches.is_empty() {
+                anyhow::bail!("No aliases match '{}'", query.join(" "));
+            }
+            // `-n 0` still shows the best match.
+            let limit = limit.max(1);
+            let more = matches.len().saturating_sub(limit);
+            matches.truncate(limit);
+            if open {
+                let alias = if matches.len() == 1 || cli.no_input || !std::io::stdin().is_terminal()
+                {
+                    Some(matches.swap_remove(0).0)
+                } else {
+                    let rows: Vec<(String, String)> = matches
+                        .into_iter()
+                        .map(|(alias, entry)| {
+                            let url = entry.display_url();
+                            (alias, url)
+                        })
+                        .collect();
+                    picker::pick(&rows)?
+                };
+                if let Some(alias) = alias {
+                    open_alias(&cli, &config, &alias, &[])?;
                 }
+            } else {
+                output::page(&format_long(matches, None));
+                if more > 0 && !output::quiet() {
+                    eprintln!("({more} more; show them with --limit)");
+                }
+            }
+        }
+        Some(Commands::Grep {
+            pattern,
+            ignore_case,
+        }) => {
+            let regex = regex::RegexBuilder::new(&pattern)
+                .case_insensitive(ignore_case)
+                .build()
+                .with_context(|| format!("Invalid pattern '{}'", pattern))?;
+            let matches = config::grep_aliases(&regex)?;
+            if matches.is_empty() {
+                anyhow::bail!("No alias URL matches '{}'", pattern);
+            }
+            let max_len = matches.iter().map(|(a, _)| a.len()).max().unwrap_or(0);
+            for (alias, url) in matches {
+                println!("{:<width$}  {}", alias, url, width = max_len);
             }
         }
+        Some(Commands::Dedupe) => {
+            let config = config::load()?;
+            let duplicates = config::duplicate_aliases(&config);
+            if duplicates.is_empty() {
+                info!("No duplicate aliases.");
+                return Ok(());
+            }
+            let interactive = !cli.no_input && std::io::stdin().is_terminal();
+            for (i, names) in duplicates.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                let width = names.iter().map(|n| n.len()).max().unwrap_or(0);
+                for name in names {
+                    println!("{:<width$}  {}", name, config.aliases[name].display_url());
+                }
+                if !interactive {
+                    continue;
+                }
+                // Suggest keeping the alias used most, the first one on a tie.
+                let mut counts = Vec::new();
+                for name in names {
+                    counts.push(state::usage(name)?.count);
+                }
+                let most_used = (0..names.len())
+                    .rev()
+                    .max_by_key(|&i| counts[i])
+                    .unwrap_or(0);
+                let mut items: Vec<String> = names
+                    .iter()
+                    .map(|name| format!("Keep '{name}' and remove the others"))
+                    .collect();
+                items.push("Skip".to_string());
+                let choice = dialoguer::Select::new()
+                    .with_prompt(format!("{} aliases open the same page", names.len()))
+                    .items(&items)
+                    .default(most_used)
+                    .interact()?;
+                let Some(keep) = names.get(choice) else {
+                    continue;
+                };
+                let others: Vec<String> = names.iter().filter(|n| *n != keep).cloned().collect();
+                config::merge_aliases(keep, &others)?;
+                let quoted: Vec<String> = others.iter().map(|a| format!("'{a}'")).collect();
+                info!(
+                    "Kept '{keep}'; moved {} to the trash (`web restore` brings them back)",
+                    quoted.join(", ")
+                );
+            }
+            if !interactive && !output::quiet() {
+                eprintln!("Run `web dedupe` in a terminal to merge them");
+            }
+        }
+        Some(Commands::Exists { alias }) => {
+            // Ambiguous names don't exist either, as they open nothing.
+            let found = matches!(config::find_alias(&config::load()?, &alias), Ok(Some(_)));
+            if !found {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Info { alias }) => {
+            let config = config::load()?;
+            let Some((entry, table)) = config::get_alias(&config, &alias) else {
+                match config::suggest_alias(&config, &alias) {
+                    Some(s) => anyhow::bail!("Alias '{}' not found; did you mean '{}'?", alias, s),
+                    None => anyhow::bail!("Alias '{}' not found", alias),
+                }
+            };
+            print_alias_info(&config, &alias, entry, table)?;
+        }
+        Some(Commands::Tag(TagCommand::Add { alias, tags })) => {
+            let added = config::tag_alias(&alias, &tags)?;
+            if added.is_empty() {
+                info!("'{alias}' already has those tags");
+            } else {
+                info!("Tagged '{alias}': {}", added.join(", "));
+            }
+        }
+        Some(Commands::Tag(TagCommand::Remove { alias, tags })) => {
+            config::untag_alias(&alias, &tags)?;
+            info!("Untagged '{alias}': {}", tags.join(", "));
+        }
+        Some(Commands::Tag(TagCommand::Rename { old, new })) => {
+            let count = config::rename_tag(&old, &new, false)?;
+            info!("Renamed tag '{old}' to '{new}' on {count} alias(es)");
+        }
+        Some(Commands::Tag(TagCommand::Merge { from, into })) => {
+            let count = config::rename_tag(&from, &into, true)?;
+            info!("Merged tag '{from}' into '{into}' on {count} alias(es)");
+        }
+        Some(Commands::Tag(TagCommand::List)) => {
+            let tags = config::list_tags()?;
+            if tags.is_empty() {
+                info!("No tags in use.");
+            }
+            let max_len = tags.keys().map(|t| t.len()).max().unwrap_or(0);
+            for (tag, aliases) in tags {
+                println!("{:<width$}  {}", tag, aliases.join(", "), width = max_len);
+            }
+        }
+        Some(Commands::Group(GroupCommand::Add { name, aliases })) => {
+            config::add_group(&name, &aliases)?;
+            info!(
+                "Group '{name}' -> {}",
+                config::parse_aliases(&aliases).join(", ")
+            );
+        }
+        Some(Commands::Group(GroupCommand::Open { ref name })) => {
+            let config = config::load()?;
+            let aliases = config::resolve_group(&config, name)?;
+            open_aliases(&cli, &config, &aliases, &[])?;
+        }
+        Some(Commands::Group(GroupCommand::Remove { name })) => {
+            config::remove_group(&name)?;
+            info!("Removed group '{name}'");
+        }
+        Some(Commands::Group(GroupCommand::List)) => {
+            let groups = config::load()?.groups;
+            if groups.is_empty() {
+                info!("No groups registered.");
+            }
+            let max_len = groups.keys().map(|g| g.len()).max().unwrap_or(0);
+            for (name, aliases) in groups {
+                println!("{:<width$}  {}", name, aliases.join(", "), width = max_len);
+            }
+        }
+        Some(Commands::Session(SessionCommand::Save { name, mut urls })) => {
+            if urls.is_empty() {
+                if std::io::stdin().is_terminal() {
+                    anyhow::bail!(
+                        "No URLs given. Pass them as arguments or pipe them in, one per line."
+                    );
+                }
+                urls = session::read_urls_from_stdin()?;
+            }
+            let count = urls.len();
+            session::save_session(&name, urls)?;
+            info!("Saved session '{name}' ({count} URL(s))");
+        }
+        Some(Commands::Session(SessionCommand::Open { ref name })) => {
+            let urls = session::resolve_session(name)?;
+            let config = config::load()?;
+            open_all(&urls, "URLs", cli.delay, |url| {
+                let entry = config::Alias::new(url);
+                open_in_browser(&cli, &config, &entry, config.default_browser.as_deref())
+            })?;
+        }
+        Some(Commands::Session(SessionCommand::Remove { name })) => {
+            session::remove_session(&name)?;
+            info!("Removed session '{name}'");
+        }
+        Some(Commands::Session(SessionCommand::List)) => {
+            let sessions = session::load()?;
+            if sessions.is_empty() {
+                info!("No sessions saved.");
+            }
+            for (name, urls) in sessions {
+                println!("{} ({} URL(s))", name, urls.len());
+                for url in urls {
+                    println!("  {}", url);
+                }
+            }
+        }
+        Some(Commands::Open { words, tag: None }) => {
+            let cli = Cli {
+                command: None,
+                alias: words,
+                ..cli
+            };
+            open_words(&cli, &config::load()?)?;
+        }
+        Some(Commands::Open {
+            tag: Some(ref tag), ..
+        }) => {
+            let config = config::load()?;
+            let aliases = config::aliases_with_tag(&config, tag);
+            if aliases.is_empty() {
+                anyhow::bail!("No aliases tagged '{}'", tag);
+            }
+            if aliases.len() > CONFIRM_OPEN_THRESHOLD && !cli.yes {
+                let prompt = format!("Open {} aliases tagged '{}'?", aliases.len(), tag);
+                if cli.no_input || !std::io::stdin().is_terminal() {
+                    anyhow::bail!("{} Pass --yes to confirm.", prompt);
+                }
+                if !dialoguer::Confirm::new()
+                    .with_prompt(prompt)
+                    .default(false)
+                    .interact()?
+                {
+                    return Ok(());
+                }
+            }
+            open_aliases(&cli, &config, &aliases, &[])?;
+        }
+        Some(Commands::Edit {
+            alias,
+            url,
+            no_verify,
+        }) => {
+            let config = config::load()?;
+            let current = config
+                .aliases
+                .get(&alias)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Alias '{}' not found", alias))?;
+            if !current.urls.is_empty() {
+                anyhow::bail!("'{}' has several URLs; edit them in the config", alias);
+            }
+            let url = match url {
+                Some(url) => url,
+                None if cli.no_input || !std::io::stdin().is_terminal() => {
+                    anyhow::bail!("No URL given for '{}'", alias)
+                }
+                None => dialoguer::Input::<String>::new()
+                    .with_prompt(&alias)
+                    .with_initial_text(&current.url)
+                    .interact_text()?,
+            };
+            if url == current.url {
+                info!("'{alias}' unchanged");
+                return Ok(());
+            }
+            if let Some(target) = url.strip_prefix('@') {
+                config::resolve_alias(&config, target)?;
+            } else {
+                if !no_verify {
+                    template::validate(&url)?;
+                }
+                if !cli.force {
+                    browser::check_scheme(&config, &url)?;
+                }
+            }
+            config::update_alias(&alias, |entry| entry.url = url.clone())?;
+            info!("'{alias}': {} -> {url}", current.url);
+        }
+        Some(Commands::Pin { aliases }) => {
+            config::set_pinned(&aliases, true)?;
+            let quoted: Vec<String> = aliases.iter().map(|a| format!("'{a}'")).collect();
+            info!("Pinned {}", quoted.join(", "));
+        }
+        Some(Commands::Unpin { aliases }) => {
+            config::set_pinned(&aliases, false)?;
+            let quoted: Vec<String> = aliases.iter().map(|a| format!("'{a}'")).collect();
+            info!("Unpinned {}", quoted.join(", "));
+        }
+        Some(Commands::Cp { alias, new }) => {
+            check_alias_name(&new)?;
+            config::copy_alias(&alias, &new, cli.force)?;
+            state::record_added(&[&new])?;
+            info!("Copied '{alias}' to '{new}'");
+        }
+        Some(Commands::Rename { old, new }) => {
+            check_alias_name(&new)?;
+            config::rename_alias(&old, &new, cli.force)?;
+            state::rename_alias(&old, &new)?;
+            info!("Renamed '{old}' to '{new}'");
+        }
+        Some(Commands::Archive { alias, store }) => {
+            let entry = config::resolve_alias(&config::load()?, &alias)?;
+            if entry.url.is_empty() || entry.takes_args() {
+                anyhow::bail!("'{}' has no single URL to archive", alias);
+            }
+            if !output::quiet() {
+                eprintln!("Saving {} to the Wayback Machine...", entry.url);
+            }
+            let snapshot = archive_url(&entry.url)?;
+            if store {
+                config::update_alias(&alias, |entry| entry.archive = Some(snapshot.clone()))?;
+            }
+            println!("{snapshot}");
+        }
+        Some(Commands::Random { ref tag }) => {
+            let config = config::load()?;
+            let aliases = match tag {
+                Some(tag) => config::aliases_with_tag(&config, tag),
+                None => config::all_aliases(&config).into_keys().collect(),
+            };
+            if aliases.is_empty() {
+                match tag {
+                    Some(tag) => anyhow::bail!("No aliases tagged '{}'", tag),
+                    None => anyhow::bail!("No aliases registered."),
+                }
+            }
+            let alias = &aliases[random_index(aliases.len())];
+            if !output::quiet() {
+                eprintln!("Opening '{}'", alias);
+            }
+            open_alias(&cli, &config, alias, &[])?;
+        }
+        Some(Commands::Secret(SecretCommand::Set { name })) => {
+            let value = if std::io::stdin().is_terminal() {
+                dialoguer::Password::new()
+                    .with_prompt(format!("Value for '{name}'"))
+                    .interact()?
+            } else {
+                let mut value = String::new();
+                std::io::Read::read_to_string(&mut std::io::stdin(), &mut value)?;
+                value.trim_end_matches(['\r', '\n']).to_string()
+            };
+            secret::set(&name, &value)?;
+            info!("Stored secret '{name}'");
+        }
+        Some(Commands::Secret(SecretCommand::Get { name })) => {
+            println!("{}", secret::get(&name)?);
+        }
+        Some(Commands::Secret(SecretCommand::Remove { name })) => {
+            secret::remove(&name)?;
+            info!("Removed secret '{name}'");
+        }
+        Some(Commands::CompleteRecent { alias }) => {
+            for value in recent::all_values(&alias)? {
+                println!("{}", value.replace('\\', "\\\\").replace(':', "\\:"));
+            }
+        }
+        Some(Commands::CompleteTags) => {
+            for (tag, aliases) in config::list_tags()? {
+                println!("{}:{} alias(es)", tag.replace(':', "\\:"), aliases.len());
+            }
+        }
+        Some(Commands::CompleteSessions) => {
+            for (name, urls) in session::load()? {
+                println!("{}:{} URL(s)", name.replace(':', "\\:"), urls.len());
+            }
+        }
+        Some(Commands::CompleteTrash) => {
+            for (name, trashed) in config::list_trash()? {
+                let url = trashed.alias.url.replace('\\', "\\\\");
+                println!("{}:{}", name.replace(':', "\\:"), url);
+            }
+        }
+        Some(Commands::CompleteGroups) => {
+            for (name, aliases) in config::load()?.groups {
+                println!("{}:{}", name.replace(':', "\\:"), aliases.join(", "));
+            }
+        }
+        Some(Commands::Get { words }) => {
+            let cli = Cli {
+                command: None,
+                alias: words,
+                print: true,
+                ..cli
+            };
+            open_words(&cli, &config::load()?)?;
+        }
+        Some(Commands::Qr { words, png }) => {
+            let cli = Cli {
+                command: None,
+                alias: words,
+                action: UrlAction::Qr(png),
+                ..cli
+            };
+            open_words(&cli, &config::load()?)?;
+        }
+        Some(Commands::Copy { words }) => {
+            let cli = Cli {
+                command: None,
+                alias: words,
+                action: UrlAction::Copy,
+                ..cli
+            };
+            open_words(&cli, &config::load()?)?;
+        }
+        None => open_words(&cli, &config::load()?)?,
+    }
+    Ok(())
+}
+
+/// Open the aliases given as positional words, with the arguments that follow them.
+fn open_words(cli: &Cli, config: &config::Config) -> Result<()> {
+    if cli.alias.is_empty() {
+        anyhow::bail!("No alias provided. Use `web --help` for usage.");
+    }
+    if let [word] = cli.alias.as_slice() {
+        if word.ends_with('/') {
+            let aliases = config::namespace_aliases(config, word);
+            if !aliases.is_empty() {
+                trace!("'{}' is a namespace of {} alias(es)", word, aliases.len());
+                return pick_and_open(cli, config, aliases, &[]);
+            }
+        }
+    }
+    if let Some((alias, args)) = parse_bang(&cli.alias) {
+        trace!("Search alias '{}' selected with !{}", alias, alias);
+        let entry = trace::muted(|| config::resolve_alias(config, alias))?;
+        if !template::takes_args(&entry.url) {
+            anyhow::bail!(
+                "'{}' is not a search alias (its URL has no {})",
+                alias,
+                template::ARGUMENT_PLACEHOLDERS
+            );
+        }
+        return open_alias(cli, config, alias, &args);
+    }
+    if let Some(fallback) = fallback_search(config, &cli.alias)? {
+        return open_alias(cli, config, &fallback, &cli.alias);
+    }
+    let (aliases, args) = split_args(config, &cli.alias)?;
+    open_aliases(cli, config, aliases, args)
+}
+
+/// One line per alias with its URL and description, in columns.
+fn format_long(aliases: Vec<(String, config::Alias)>, highlight: Option<&str>) -> String {
+    let rows: Vec<(String, String, String)> = aliases
+        .into_iter()
+        .map(|(alias, entry)| {
+            let url = entry.display_url();
+            (alias, url, entry.description.unwrap_or_default())
+        })
+        .collect();
+    let name_len = rows.iter().map(|(a, _, _)| a.len()).max().unwrap_or(0);
+    let url_len = rows.iter().map(|(_, u, _)| u.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for (alias, url, description) in rows {
+        // Padded separately, as color codes take no room on screen.
+        let name_padding = " ".repeat(name_len - alias.len());
+        let url_padding = " ".repeat(url_len - url.len());
+        let line = format!(
+            "{}{name_padding}  {}{url_padding}  {description}",
+            output::paint(&alias, Style::Alias, highlight),
+            output::paint(&url, Style::Url, highlight)
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// `web add --stdin`: add an alias for each line piped in. Lines that can't be added are
+/// reported, and the others are added anyway.
+fn add_from_stdin(
+    template: &config::Alias,
+    no_verify: bool,
+    expand: bool,
+    force: bool,
+) -> Result<()> {
+    if std::io::stdin().is_terminal() {
+        anyhow::bail!("Pipe in lines of `alias url [tags...]`, or URLs, one per line");
+    }
+    let lines = session::read_urls_from_stdin()?;
+    let mut config = config::load()?;
+    let mut added: Vec<String> = Vec::new();
+    let mut failed = 0;
+    for line in &lines {
+        match stdin_entry(&config, line, template, no_verify, expand, force) {
+            Ok((name, entry)) => {
+                info!("Added '{name}' -> {}", entry.url);
+                config.aliases.insert(name.clone(), entry);
+                added.push(name);
+            }
+            Err(e) => {
+                eprintln!("Skipped '{line}': {e:#}");
+                failed += 1;
+            }
+        }
+    }
+    if !added.is_empty() {
+        config::save(&config)?;
+        let added: Vec<&str> = added.iter().map(String::as_str).collect();
+        state::record_added(&added)?;
+    }
+    if failed > 0 {
+        anyhow::bail!(
+            "Added {} alias(es); {} line(s) could not be added",
+            added.len(),
+            failed
+        );
+    }
+    info!("Added {} alias(es)", added.len());
+    Ok(())
+}
+
+/// The alias and entry to add for one line of `web add --stdin`.
+fn stdin_entry(
+    config: &config::Config,
+    line: &str,
+    template: &config::Alias,
+    no_verify: bool,
+    expand: bool,
+    force: bool,
+) -> Result<(String, config::Alias)> {
+    let batch::Line { alias, url, tags } = batch::parse_line(line);
+    let url = if let Some(target) = url.strip_prefix('@') {
+        if alias.as_deref() == Some(target) {
+            anyhow::bail!("Alias '{}' can't point to itself", target);
+        }
+        config::find_alias(config, target)?
+            .with_context(|| format!("Alias '{}' not found", target))?;
+        url
+    } else {
+        if !no_verify {
+            template::validate(&url)?;
+        }
+        if !force {
+            browser::check_scheme(config, &url)?;
+        }
+        if expand {
+            expand_url(url)?
+        } else {
+            url
+        }
+    };
+    let name = match alias {
+        Some(name) => {
+            if let Some(old) = config.aliases.get(&name).filter(|_| !force) {
+                anyhow::bail!(
+                    "Alias '{}' already exists ({}); pass --force to overwrite it",
+                    name,
+                    old.url
+                );
+            }
+            name
+        }
         None => {
-            let alias = cli
-                .alias
-                .as_deref()
-                .ok_or_else(|| anyhow::anyhow!("No alias provided. Use `web --help` for usage."))?;
-            let url = config::resolve_alias(alias)?;
-            browser::open_url(&url, cli.browser_choice())?;
+            if let Some(existing) = config::aliases_for_url(config, &url).first() {
+                anyhow::bail!("Already registered as '{}'", existing);
+            }
+            let title = if !url.contains('{') && !url.starts_with('@') {
+                http::title(&url).unwrap_or_else(|e| {
+                    trace!("No title for {url}: {e:#}");
+                    None
+                })
+            } else {
+                None
+            };
+            let base = batch::derive_alias(&url, title.as_deref())
+                .context("Can't name this URL; put an alias before it")?;
+            let mut name = base.clone();
+            let mut n = 2;
+            while config.aliases.contains_key(&name) || Cli::is_subcommand(&name) {
+                name = format!("{base}-{n}");
+                n += 1;
+            }
+            name
+        }
+    };
+    check_alias_name(&name)?;
+    config::check_case_collision(config, &name)?;
+    let mut entry = template.clone();
+    entry.url = url;
+    for tag in tags {
+        if !entry.tags.contains(&tag) {
+            entry.tags.push(tag);
+        }
+    }
+    Ok((name, entry))
+}
+
+/// Refuse alias names that `web <name>` can't open because a subcommand takes precedence.
+fn check_alias_name(name: &str) -> Result<()> {
+    if Cli::is_subcommand(name) {
+        anyhow::bail!(
+            "'{}' is a subcommand, so `web {}` would never open the alias; choose another name",
+            name,
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Let the user pick one of `aliases` and open it with `args`.
+fn pick_and_open(
+    cli: &Cli,
+    config: &config::Config,
+    aliases: std::collections::BTreeMap<String, config::Alias>,
+    args: &[String],
+) -> Result<()> {
+    let aliases: Vec<(String, String)> = config::pinned_first(aliases)
+        .into_iter()
+        .map(|(alias, entry)| {
+            let url = entry.display_url();
+            (alias, url)
+        })
+        .collect();
+    if let Some(alias) = picker::pick(&aliases)? {
+        open_alias(cli, config, &alias, args)?;
+    }
+    Ok(())
+}
+
+/// `web info`: one labelled line per thing known about `alias`, whose `entry` comes from
+/// the `[overrides]` table `table` if given.
+fn print_alias_info(
+    config: &config::Config,
+    alias: &str,
+    entry: config::Alias,
+    table: Option<String>,
+) -> Result<()> {
+    let mut rows: Vec<(&str, String)> = vec![("Alias", alias.to_string())];
+    let url = entry.display_url();
+    if entry.url.starts_with('@') {
+        let target = config::find_alias(config, alias)?.unwrap_or_default();
+        rows.push(("URL", format!("{} -> {}", url, target.display_url())));
+    } else {
+        rows.push(("URL", url));
+    }
+    if !entry.locales.is_empty() {
+        let locales: Vec<String> = entry
+            .locales
+            .iter()
+            .map(|(locale, url)| format!("{locale} = {url}"))
+            .collect();
+        rows.push(("Locales", locales.join(", ")));
+    }
+    let or_none = |values: &[String]| {
+        if values.is_empty() {
+            "-".to_string()
+        } else {
+            values.join(", ")
+        }
+    };
+    rows.push(("Tags", or_none(&entry.tags)));
+    rows.push(("Description", entry.description.unwrap_or("-".into())));
+    let mut browser = match (&entry.browser, &config.default_browser) {
+        (Some(browser), _) => browser.clone(),
+        (None, Some(default)) => format!("{default} (default_browser)"),
+        (None, None) => "system default".to_string(),
+    };
+    if let Some(profile) = &entry.profile {
+        browser.push_str(&format!(", profile {profile}"));
+    }
+    if entry.app {
+        browser.push_str(", app window");
+    }
+    rows.push(("Browser", browser));
+    let options: Vec<String> = [
+        (entry.confirm, "confirm"),
+        (entry.append_path, "append_path"),
+        (entry.rotate, "rotate"),
+        (entry.pinned, "pinned"),
+    ]
+    .iter()
+    .filter(|(set, _)| *set)
+    .map(|(_, name)| name.to_string())
+    .collect();
+    if !options.is_empty() {
+        rows.push(("Options", options.join(", ")));
+    }
+    if let Some(archive) = entry.archive {
+        rows.push(("Archive", archive));
+    }
+    let usage = state::usage(alias)?;
+    let created = usage.created.as_deref().map(format_time);
+    rows.push(("Created", created.unwrap_or("unknown".into())));
+    let last_used = usage.last_used.as_deref().map(format_time);
+    rows.push(("Last used", last_used.unwrap_or("never".into())));
+    rows.push(("Use count", usage.count.to_string()));
+    let mut source = config::config_path()?.display().to_string();
+    if let Some(table) = table {
+        source.push_str(&format!(" [overrides.{table}]"));
+    }
+    rows.push(("Defined in", source));
+
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.len() + 1)
+        .max()
+        .unwrap_or(0);
+    for (label, value) in rows {
+        println!("{:<width$} {}", format!("{label}:"), value);
+    }
+    Ok(())
+}
+
+/// An RFC 3339 time stored in the config or state file, as local date and minutes.
+fn format_time(time: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(time)
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or(time.to_string())
+}
+
+/// Like a DuckDuckGo bang, a word `!name` anywhere among the positional words selects the
+/// search alias `name`, and all the other words are its arguments.
+fn parse_bang(words: &[String]) -> Option<(&str, Vec<String>)> {
+    let i = words
+        .iter()
+        .position(|w| w.len() > 1 && w.starts_with('!'))?;
+    let args = words
+        .iter()
+        .enumerate()
+        .filter(|(j, _)| *j != i)
+        .map(|(_, w)| w.clone())
+        .collect();
+    Some((&words[i][1..], args))
+}
+
+/// When the first word is neither an alias, a likely typo of one, nor a URL, and the config
+/// names a `fallback_search` alias, returns that alias; all the words become its query.
+fn fallback_search(config: &config::Config, words: &[String]) -> Result<Option<String>> {
+    let Some(fallback) = config.fallback_search.clone() else {
+        return Ok(None);
+    };
+    let first = handler::alias_from_url(&words[0]).unwrap_or(&words[0]);
+    let is_known = trace::muted(|| -> Result<bool> {
+        let (first, _) = config::split_fragment(config, first)?;
+        Ok(config::find_alias(config, first)?.is_some()
+            || template::direct_url(first).is_some()
+            || config::suggest_alias(config, first).is_some()
+            || !config::prefix_matches(config, first).is_empty())
+    })?;
+    if is_known {
+        return Ok(None);
+    }
+    match config.aliases.get(&fallback) {
+        Some(entry) if template::takes_args(&entry.url) => {
+            trace!(
+                "'{}' is not an alias or URL; searching with fallback_search '{}'",
+                first,
+                fallback
+            );
+            Ok(Some(fallback))
+        }
+        Some(_) => anyhow::bail!(
+            "fallback_search '{}' is not a search alias (its URL has no {})",
+            fallback,
+            template::ARGUMENT_PLACEHOLDERS
+        ),
+        None => anyhow::bail!("fallback_search alias '{}' not found", fallback),
+    }
+}
+
+/// Split the positional words into aliases to open and, when one of them takes
+/// arguments, the words after it, which become its arguments.
+fn split_args<'a>(
+    config: &config::Config,
+    words: &'a [String],
+) -> Result<(&'a [String], &'a [String])> {
+    for (i, word) in words.iter().enumerate() {
+        let alias = handler::alias_from_url(word).unwrap_or(word);
+        let takes_args = trace::muted(|| -> Result<bool> {
+            let (alias, _) = config::split_fragment(config, alias)?;
+            Ok(config::find_alias(config, alias)?.is_some_and(|e| e.takes_args()))
+        })?;
+        if takes_args {
+            return Ok(words.split_at(i + 1));
+        }
+    }
+    Ok((words, &[]))
+}
+
+fn open_aliases(
+    cli: &Cli,
+    config: &config::Config,
+    aliases: &[String],
+    args: &[String],
+) -> Result<()> {
+    open_all(aliases, "aliases", cli.delay, |alias| {
+        // Links such as `web://jira` arrive here through the registered scheme handler.
+        open_alias(
+            cli,
+            config,
+            handler::alias_from_url(alias).unwrap_or(alias),
+            args,
+        )
+    })
+}
+
+/// Run `open` on each item in order, pausing for `delay` between items so pages don't all
+/// load at once and tabs keep their order. A failure is reported and the rest are still opened.
+fn open_all(
+    items: &[String],
+    noun: &str,
+    delay: Option<Duration>,
+    open: impl Fn(&str) -> Result<()>,
+) -> Result<()> {
+    let mut failed = 0;
+    for (i, item) in items.iter().enumerate() {
+        if let Some(delay) = delay.filter(|_| i > 0) {
+            std::thread::sleep(delay);
+        }
+        if let Err(e) = open(item) {
+            if items.len() == 1 {
+                return Err(e);
+            }
+            eprintln!("Error: {}: {:#}", item, e);
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} {} could not be opened", failed, items.len(), noun);
+    }
+    Ok(())
+}
+
+/// A random index below `len`. The standard library's randomly seeded hasher is plenty
+/// for picking a page to read.
+fn random_index(len: usize) -> usize {
+    use std::hash::{BuildHasher, Hasher};
+    let seed = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    (seed % len as u64) as usize
+}
+
+/// Open `alias`, filling its placeholders with `args` if it takes arguments. Input that is
+/// not an alias but a URL or bare domain is opened as is.
+fn open_alias(cli: &Cli, config: &config::Config, alias: &str, args: &[String]) -> Result<()> {
+    let (alias, fragment) = config::split_fragment(config, alias)?;
+    let mut entry = match config::resolve_alias(config, alias) {
+        Ok(entry) => entry,
+        Err(e) => match template::direct_url(alias) {
+            Some(url) => {
+                trace!("'{}' is not an alias; opening it as a URL", alias);
+                config::Alias::new(url)
+            }
+            None => {
+                let matches = config::prefix_matches(config, alias);
+                if matches.len() > 1 && !cli.no_input {
+                    trace!("'{}' is the start of {} aliases", alias, matches.len());
+                    let mut aliases = config::all_aliases(config);
+                    aliases.retain(|name, _| matches.contains(name));
+                    return pick_and_open(cli, config, aliases, args);
+                }
+                return Err(e);
+            }
+        },
+    };
+    if let Some(locale) = current_locale(cli) {
+        if let Some(url) = entry.locale_url(&locale) {
+            trace!("Using the URL for locale {}", locale);
+            entry.url = url.to_string();
         }
     }
+    let urls = std::mem::take(&mut entry.urls);
+    if entry.rotate && !urls.is_empty() {
+        let next = state::next_rotation(alias, urls.len())?;
+        trace!("Rotating to URL {} of {}", next + 1, urls.len());
+        entry.url = urls[next].clone();
+    }
+    if entry.rotate || urls.is_empty() {
+        open_entry(cli, config, alias, entry, args, fragment)?;
+    } else {
+        open_all(&urls, "URLs", cli.delay, |url| {
+            let entry = config::Alias {
+                url: url.to_string(),
+                ..entry.clone()
+            };
+            open_entry(cli, config, alias, entry, args, fragment)
+        })?;
+    }
+    // Only aliases in the config have a history; not URLs, rewrites or wildcard matches.
+    if !cli.dry_run && trace::muted(|| config::get_alias(config, alias)).is_some() {
+        state::record_use(alias)?;
+    }
     Ok(())
 }
 
+/// The locale given with --locale, else the one the environment selects for messages.
+fn current_locale(cli: &Cli) -> Option<String> {
+    cli.locale.clone().or_else(|| {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .filter(|value| value != "C" && value != "POSIX")
+    })
+}
+
+/// Open the single URL of `entry`, the target of `alias`, at `fragment` if given (--fragment
+/// takes precedence).
+fn open_entry(
+    cli: &Cli,
+    config: &config::Config,
+    alias: &str,
+    mut entry: config::Alias,
+    args: &[String],
+    fragment: Option<&str>,
+) -> Result<()> {
+    trace!("'{}' -> {}", alias, entry.url);
+    entry.url = template::expand_vars(&entry.url, &config.vars)?;
+    entry.url = template::expand_secrets(&entry.url, secret::get)?;
+    entry.url = template::expand_commands(&entry.url, config.allow_exec)?;
+    entry.url = template::expand_functions(&entry.url, clipboard::read)?;
+    if template::takes_args(&entry.url) {
+        let (url, used) =
+            template::fill_args(&entry.url, args, |param| ask_param(cli, alias, param))?;
+        for (param, value) in &used {
+            recent::remember(alias, param, value)?;
+        }
+        entry.url = url;
+    } else if entry.append_path && !args.is_empty() {
+        entry.url = template::append_path(&entry.url, args)?;
+    }
+    // After the arguments are filled in, as parsing the URL would encode its placeholders.
+    if !cli.params.is_empty() {
+        entry.url = template::set_query_params(&entry.url, &cli.params)?;
+    }
+    if let Some(fragment) = cli.fragment.as_deref().or(fragment) {
+        entry.url = template::set_fragment(&entry.url, fragment)?;
+    }
+    if entry.confirm && !cli.yes && !cli.skips_browser() && !cli.dry_run {
+        let prompt = format!("Open {}?", entry.url);
+        if cli.no_input || !std::io::stdin().is_terminal() {
+            anyhow::bail!("'{}' asks before opening. Pass --yes to confirm.", alias);
+        }
+        if !dialoguer::Confirm::new()
+            .with_prompt(prompt)
+            .default(false)
+            .interact()?
+        {
+            return Ok(());
+        }
+    }
+    let preferred = entry
+        .browser
+        .as_deref()
+        .or(config.default_browser.as_deref());
+    if trace::enabled() && !cli.skips_browser() {
+        let reason = if cli.browser_flag().is_some() {
+            "given on the command line"
+        } else if entry.browser.is_some() {
+            "set on the alias"
+        } else if config.default_browser.is_some() {
+            "default_browser in the config"
+        } else {
+            "no browser configured"
+        };
+        trace!(
+            "Opening {} in {} ({})",
+            entry.url,
+            cli.browser_choice(preferred),
+            reason
+        );
+    }
+    open_in_browser(cli, config, &entry, preferred)
+}
+
+/// Open the URL of `entry` in the browser chosen on the command line, else `preferred`, or
+/// with --all-browsers in every installed browser. With --print, `web copy` or `web qr`,
+/// only print, copy or draw the URL.
+fn open_in_browser(
+    cli: &Cli,
+    config: &config::Config,
+    entry: &config::Alias,
+    preferred: Option<&str>,
+) -> Result<()> {
+    if cli.print {
+        println!("{}", entry.url);
+        return Ok(());
+    }
+    match &cli.action {
+        UrlAction::Open => {}
+        UrlAction::Copy => {
+            clipboard::write(&entry.url)?;
+            info!("Copied {}", entry.url);
+            return Ok(());
+        }
+        UrlAction::Qr(None) => {
+            print!("{}", qr::render(&entry.url)?);
+            info!("{}", entry.url);
+            return Ok(());
+        }
+        UrlAction::Qr(Some(path)) => {
+            qr::write_png(&entry.url, path)?;
+            info!("Wrote a QR code of {} to {}", entry.url, path.display());
+            return Ok(());
+        }
+    }
+    if !cli.all_browsers {
+        let browser = cli.browser_choice(preferred);
+        return browser::open_url(config, &entry.url, &browser, &cli.open_options(entry));
+    }
+    let names: Vec<String> = browser::available_browsers(config)?
+        .into_iter()
+        .filter(|b| b.installed)
+        .map(|b| b.name)
+        .collect();
+    if names.is_empty() {
+        anyhow::bail!("No installed browsers found. Use `web browsers --all` to see all.");
+    }
+    open_all(&names, "browsers", cli.delay, |name| {
+        let browser = BrowserChoice::Named(name.to_string());
+        browser::open_url(config, &entry.url, &browser, &cli.open_options(entry))
+    })
+}
+
+/// Ask the Wayback Machine to save `url` and return the snapshot URL it redirects to.
+fn archive_url(url: &str) -> Result<String> {
+    const SNAPSHOT_PREFIX: &str = "https://web.archive.org/web/";
+    // Saving fetches the page first, which can take a while.
+    let response = http::get(&format!("https://web.archive.org/save/{url}"), 120)?;
+    if !response.final_url.starts_with(SNAPSHOT_PREFIX) {
+        anyhow::bail!(
+            "The Wayback Machine did not save {} ({} {})",
+            url,
+            response.status,
+            http::status_text(response.status)
+        );
+    }
+    Ok(response.final_url)
+}
+
+/// The URL on the clipboard, shown for confirmation (unless --yes) before adding it as
+/// `aliases`; `None` if it was declined. A bare domain gets a scheme as when opening it.
+fn url_from_clipboard(aliases: &str, yes: bool, interactive: bool) -> Result<Option<String>> {
+    let text = clipboard::read()?;
+    let Some(url) = template::direct_url(text.trim()) else {
+        anyhow::bail!("The clipboard does not hold a URL");
+    };
+    if yes {
+        info!("Using {url} from the clipboard");
+        return Ok(Some(url));
+    }
+    let prompt = format!("Add '{aliases}' -> {url}?");
+    if !interactive {
+        anyhow::bail!("{} Pass --yes to confirm.", prompt);
+    }
+    let confirmed = dialoguer::Confirm::new()
+        .with_prompt(prompt)
+        .default(true)
+        .interact()?;
+    Ok(confirmed.then_some(url))
+}
+
+/// Follow the redirects of a short URL and return its destination.
+fn expand_url(url: String) -> Result<String> {
+    let response = http::head(&url)?;
+    if response.status >= 400 {
+        anyhow::bail!(
+            "{} returned {} {}; the short URL may have expired",
+            url,
+            response.status,
+            http::status_text(response.status)
+        );
+    }
+    if response.final_url != url {
+        info!("Expanded {url} -> {}", response.final_url);
+    }
+    Ok(response.final_url)
+}
+
+/// Request `url` and report its status. When redirects lead elsewhere (http to https, a
+/// canonical trailing slash, ...), offer to store the final URL instead.
+fn check_url(url: String, interactive: bool) -> Result<String> {
+    if template::takes_args(&url) || url.contains('{') {
+        info!("Not checking {url}: it contains placeholders");
+        return Ok(url);
+    }
+    let response = http::head(&url)?;
+    info!(
+        "{} {} {}",
+        response.status,
+        http::status_text(response.status),
+        response.final_url
+    );
+    if response.final_url == url {
+        return Ok(url);
+    }
+    let prompt = format!("Store {} instead?", response.final_url);
+    if interactive
+        && dialoguer::Confirm::new()
+            .with_prompt(prompt)
+            .default(true)
+            .interact()?
+    {
+        return Ok(response.final_url);
+    }
+    Ok(url)
+}
+
+/// Ask for the value of `{param}`, offering the most recently used one as the default.
+fn ask_param(cli: &Cli, alias: &str, param: &str) -> Result<String> {
+    if cli.no_input || !std::io::stdin().is_terminal() {
+        anyhow::bail!("Alias '{}' needs a value for {{{}}}", alias, param);
+    }
+    let mut input = dialoguer::Input::<String>::new().with_prompt(format!("{alias} {{{param}}}"));
+    if let Some(last) = recent::values(alias, param)?.into_iter().next() {
+        input = input.default(last);
+    }
+    Ok(input.interact_text()?)
+}
+
 fn zsh_completion_script() -> &'static str {
     r#"#compdef web
 
@@ -103,10 +1640,44 @@ _web() {
     typeset -A opt_args
 
     _arguments -s -S \
-        '(--chrome --firefox --brave)--safari[Use Safari browser]' \
-        '(--safari --firefox --brave)--chrome[Use Chrome browser]' \
-        '(--safari --chrome --brave)--firefox[Use Firefox browser]' \
-        '(--safari --chrome --firefox)--brave[Use Brave browser]' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--safari[Use Safari browser]' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--chrome[Use Chrome browser]' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--firefox[Use Firefox browser]' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--brave[Use Brave browser]' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--edge[Use Microsoft Edge browser]' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--arc[Use Arc browser]' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--vivaldi[Use Vivaldi browser]' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--opera[Use Opera browser]' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--librewolf[Use LibreWolf browser]' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--chromium[Use Chromium browser]' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--zen[Use Zen browser]' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--browser[Browser to open the URL in]:browser:_web_browsers' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--tui-browser=-[Open in a text browser in this terminal]::browser:(w3m lynx browsh elinks links)' \
+        '(--safari --chrome --firefox --brave --edge --arc --vivaldi --opera --librewolf --chromium --zen --browser --tui-browser --all-browsers)--all-browsers[Open in every installed browser]' \
+        '(--incognito --private)'{--incognito,--private}'[Open in a private/incognito window]' \
+        '(--new-tab)--new-window[Open in a new browser window]' \
+        '(--new-window)--new-tab[Open in a new tab of the current window]' \
+        '--profile[Browser profile to open in]:profile:' \
+        '--new-instance[Start a separate browser process (Firefox)]' \
+        '--app[Open as a chromeless app window]' \
+        '--kiosk[Open fullscreen in kiosk mode]' \
+        '--background[Open without bringing the browser to the foreground]' \
+        '--wait[Wait until the browser exits before returning]' \
+        '--activate-existing[Focus a tab already showing the URL]' \
+        '--cdp=-[Open via the DevTools port of a running browser]::port:' \
+        '--delay[Pause between pages when opening several]:duration (e.g. 500ms):' \
+        '--dry-run[Print the command that would open each URL without running it]' \
+        '--no-input[Never prompt; fail when a template argument is missing]' \
+        '*--param[Add or replace a query parameter]:key=value:' \
+        '--locale[Locale whose URL variant to open]:locale:' \
+        '--fragment[Jump to this #fragment of the page]:fragment:' \
+        '(-y --yes)'{-y,--yes}'[Do not ask for confirmation]' \
+        '--print[Print the resolved URL instead of opening it]' \
+        '(-v --verbose)'{-v,--verbose}'[Explain how the input was resolved and opened]' \
+        '(-q --quiet)'{-q,--quiet}'[Do not print informational messages]' \
+        '--no-color[Do not color output]' \
+        '--no-pager[Do not page long listings]' \
+        '--force[Allow dangerous URL schemes and overwrite existing aliases]' \
         '(- *)--help[Print help]' \
         '(- *)--version[Print version]' \
         '1: :_web_first_arg' \
@@ -116,35 +1687,280 @@ _web() {
     case $state in
         subcmd)
             case $line[1] in
+                hotkey-daemon)
+                    _arguments \
+                        '--key[Shortcut to listen for]:shortcut:(ctrl+alt+w cmd+shift+space)'
+                    ;;
+                doctor)
+                    _arguments \
+                        '--fix[Repair the problems that can be repaired automatically]'
+                    ;;
+                browsers)
+                    _arguments \
+                        '--all[Also show browsers that are not installed]'
+                    ;;
                 add)
                     _arguments \
-                        '1:aliases:' \
-                        '2:url:_urls'
+                        '--browser[Browser to always open these alias(es) in]:browser:_web_browsers' \
+                        '--profile[Browser profile to always open these alias(es) in]:profile:' \
+                        '--app[Always open these alias(es) as an app window]' \
+                        '--confirm[Ask before opening these alias(es)]' \
+                        '--no-verify[Store the URL without checking that it is valid]' \
+                        '--check[Request the URL and offer to store where redirects lead]' \
+                        '--expand[Resolve a short URL and store its destination]' \
+                        '(2 --edit)--clipboard[Take the URL from the clipboard]' \
+                        '(1 2 --clipboard --check --edit)--stdin[Read alias url \[tags...\] lines from stdin]' \
+                        '(--stdin --clipboard)--edit[Write the alias and URL in $EDITOR]' \
+                        '--append-path[Join extra words onto the URL path]' \
+                        '*--tag[Tag these alias(es)]:tag:_web_tags' \
+                        '--desc[Note on what the alias is for]:description:' \
+                        '--pin[List these alias(es) first]' \
+                        '(--stdin)1:aliases:' \
+                        '(--stdin)2:url:_urls'
                     ;;
                 remove)
                     _arguments \
-                        '1:aliases:_web_aliases'
+                        '(1)--all[Remove every alias after backing up the config]' \
+                        '(--all)1:aliases:_web_aliases'
+                    ;;
+                random)
+                    _arguments \
+                        '--tag[Only choose among aliases carrying this tag]:tag:_web_tags'
+                    ;;
+                open)
+                    _arguments \
+                        '(*)--tag[Tag to open]:tag:_web_tags' \
+                        '(--tag)*:alias:_web_aliases'
+                    ;;
+                list)
+                    _arguments \
+                        '--tag[Only list aliases carrying this tag]:tag:_web_tags' \
+                        '(-l --long --format --group-by)'{-l,--long}'[One alias per line, with its description]' \
+                        '(-l --long --group-by)--format[Machine-readable output]:format:(json tsv csv plain)' \
+                        '(-l --long --format)--group-by[Cluster aliases under headers]:key:(domain)' \
+                        '1:pattern:'
+                    ;;
+                secret)
+                    if (( CURRENT == 2 )); then
+                        local -a secret_cmds=(
+                            'set:Store a secret in the OS keychain'
+                            'get:Print a stored secret'
+                            'remove:Delete a stored secret'
+                        )
+                        _describe 'secret command' secret_cmds
+                    fi
+                    ;;
+                session)
+                    if (( CURRENT == 2 )); then
+                        local -a session_cmds=(
+                            'save:Save URLs as a session'
+                            'open:Open every URL in a session'
+                            'remove:Remove a session'
+                            'list:List all sessions'
+                        )
+                        _describe 'session command' session_cmds
+                    else
+                        case $line[2] in
+                            save) (( CURRENT > 3 )) && _urls ;;
+                            open|remove) (( CURRENT == 3 )) && _web_sessions ;;
+                        esac
+                    fi
+                    ;;
+                group)
+                    if (( CURRENT == 2 )); then
+                        local -a group_cmds=(
+                            'add:Create or replace a group'
+                            'open:Open every alias in a group'
+                            'remove:Remove a group'
+                            'list:List all groups'
+                        )
+                        _describe 'group command' group_cmds
+                    else
+                        case $line[2] in
+                            add) (( CURRENT == 4 )) && _web_aliases ;;
+                            open|remove) (( CURRENT == 3 )) && _web_groups ;;
+                        esac
+                    fi
                     ;;
                 completions)
                     _arguments \
                         '1:shell:(bash zsh fish elvish powershell)'
                     ;;
+                export)
+                    _arguments \
+                        '--shortcuts[Write one macOS Shortcut per alias into a directory]:directory:_files -/' \
+                        '--tag[Only export aliases carrying this tag]:tag:_web_tags'
+                    ;;
                 import)
                     _arguments \
                         '1:path:_files -g "*.toml"'
                     ;;
+                archive)
+                    _arguments \
+                        '--store[Remember the snapshot URL on the alias]' \
+                        '1:alias:_web_aliases'
+                    ;;
+                rename)
+                    _arguments \
+                        '1:alias:_web_aliases' \
+                        '2:new name:'
+                    ;;
+                edit)
+                    _arguments \
+                        '--no-verify[Store the URL without checking that it is valid]' \
+                        '1:alias:_web_aliases' \
+                        '2:url:_urls'
+                    ;;
+                cp)
+                    _arguments \
+                        '1:alias:_web_aliases' \
+                        '2:new name:'
+                    ;;
+                restore)
+                    (( CURRENT == 2 )) && _web_trash
+                    ;;
+                trash)
+                    if (( CURRENT == 2 )); then
+                        local -a trash_cmds=(
+                            'list:List removed aliases'
+                            'empty:Delete every removed alias for good'
+                        )
+                        _describe 'trash command' trash_cmds
+                    fi
+                    ;;
+                get)
+                    if (( CURRENT == 2 )); then
+                        _web_aliases
+                    else
+                        _web_recent "$line[2]"
+                    fi
+                    ;;
+                copy)
+                    if (( CURRENT == 2 )); then
+                        _web_aliases
+                    else
+                        _web_recent "$line[2]"
+                    fi
+                    ;;
+                qr)
+                    _arguments \
+                        '--png[Write the QR code to this PNG file]:file:_files' \
+                        '1:alias:_web_aliases' \
+                        '*:argument:->args'
+                    [[ $state == args ]] && _web_recent "$line[1]"
+                    ;;
+                tag)
+                    if (( CURRENT == 2 )); then
+                        local -a tag_cmds=(
+                            'add:Tag an alias'
+                            'remove:Remove tags from an alias'
+                            'rm:Remove tags from an alias'
+                            'list:List all tags and their aliases'
+                            'rename:Rename a tag on every alias'
+                            'merge:Merge a tag into another'
+                        )
+                        _describe 'tag command' tag_cmds
+                    else
+                        case $line[2] in
+                            add|remove|rm)
+                                if (( CURRENT == 3 )); then
+                                    _web_aliases
+                                else
+                                    _web_tags
+                                fi
+                                ;;
+                            rename)
+                                (( CURRENT == 3 )) && _web_tags
+                                ;;
+                            merge)
+                                _web_tags
+                                ;;
+                        esac
+                    fi
+                    ;;
+                info)
+                    _arguments \
+                        '1:alias:_web_aliases'
+                    ;;
+                pin)
+                    _arguments \
+                        '*:alias:_web_aliases'
+                    ;;
+                unpin)
+                    _arguments \
+                        '*:alias:_web_aliases'
+                    ;;
+                tree)
+                    _arguments \
+                        '--tag[Only list aliases carrying this tag]:tag:_web_tags' \
+                        '1:namespace:_web_aliases'
+                    ;;
+                search)
+                    _arguments \
+                        '--open[Open the best match]' \
+                        '(-n --limit)'{-n,--limit}'[Show at most this many matches]:count:' \
+                        '*:query:'
+                    ;;
+                grep)
+                    _arguments \
+                        '(-i --ignore-case)'{-i,--ignore-case}'[Match regardless of case]' \
+                        '1:pattern:'
+                    ;;
+                lint)
+                    _arguments \
+                        '--json[Print the problems as JSON]' \
+                        '1:config file:_files -g "*.toml"'
+                    ;;
+                exists)
+                    _arguments \
+                        '1:alias:_web_aliases'
+                    ;;
                 help)
                     local -a subcmds=(
                         'add:Register new alias(es)'
+                        'archive:Save an alias to the Wayback Machine'
+                        'browsers:List installed browsers'
                         'completions:Generate shell completions'
+                        'copy:Copy the URL of an alias'
+                        'cp:Copy an alias'
+                        'dedupe:Merge aliases of the same page'
+                        'doctor:Check the setup for problems'
+                        'edit:Change the URL of an alias'
+                        'exists:Check that an alias exists'
                         'export:Export current alias settings to stdout'
+                        'get:Print the URL of an alias'
+                        'grep:Find aliases by URL regex'
+                        'group:Manage and open groups of aliases'
                         'help:Print this message or the help of the given subcommand(s)'
+                        'hotkey-daemon:Open the picker on a global shortcut'
                         'import:Import aliases from a TOML file'
+                        'info:Show details of an alias'
+                        'lint:Check the config for mistakes'
                         'list:List all aliases'
+                        'open:Open aliases, or every alias carrying a tag'
+                        'pick:Choose an alias interactively'
+                        'pin:Pin aliases'
+                        'qr:Show the URL of an alias as a QR code'
+                        'random:Open a randomly chosen alias'
+                        'register-handler:Register web as the handler for web:// links'
                         'remove:Remove alias(es)'
+                        'rename:Rename an alias'
+                        'restore:Restore a removed alias'
+                        'search:Fuzzy-find aliases'
+                        'secret:Manage secrets referenced in alias URLs'
+                        'session:Save and reopen named sets of URLs'
+                        'tag:Add, remove and list tags'
+                        'trash:List or empty the trash'
+                        'tree:List aliases by namespace'
+                        'unpin:Unpin aliases'
                     )
                     _describe 'subcommand' subcmds
                     ;;
+                *)
+                    # Arguments of a template alias, or further aliases to open
+                    _web_recent "$line[1]"
+                    _web_aliases
+                    ;;
             esac
             ;;
     esac
@@ -153,12 +1969,41 @@ _web() {
 _web_first_arg() {
     local -a subcommands=(
         'add:Register new alias(es) — comma-separated for multiple (e.g. claude,c)'
+        'archive:Save a snapshot of an alias to the Wayback Machine and print its URL'
+        'browsers:List installed browsers and the command used to open each'
         'completions:Generate shell completions'
+        'copy:Copy the URL an alias resolves to to the clipboard'
+        'cp:Copy an alias with all its settings to a new name'
+        'dedupe:Find aliases pointing at the same page and merge them'
+        'doctor:Check the config, opener and shell completion setup for problems'
+        'edit:Change the URL of an alias, keeping its settings; without a URL, edit the current one'
+        'exists:Exit with 0 if an alias would open something, printing nothing'
         'export:Export current alias settings to stdout (TOML format)'
+        'get:Print the URL an alias resolves to without opening it'
+        'grep:Print the aliases whose URLs match a regex'
+        'group:Manage and open groups of aliases'
         'help:Print this message or the help of the given subcommand(s)'
+        'hotkey-daemon:Open the alias picker whenever a global keyboard shortcut is pressed'
         'import:Import aliases from a TOML file'
+        'info:Show everything known about an alias'
+        'lint:Check a config file for mistakes'
         'list:List all aliases'
+        'open:Open aliases (even ones named like a subcommand) or every alias carrying a tag'
+        'pick:Choose an alias interactively and open it'
+        'pin:Pin aliases so they come first in list, completions and the picker'
+        'qr:Show the URL an alias resolves to as a QR code'
+        'random:Open a randomly chosen alias'
+        'register-handler:Register web as the handler for web://alias links'
         'remove:Remove alias(es) — comma-separated for multiple (e.g. claude,c)'
+        'rename:Rename an alias, keeping its settings and updating groups that include it'
+        'restore:Bring back an alias removed with web remove'
+        'search:Fuzzy-find aliases by name, URL, tags and description'
+        'secret:Manage secrets referenced as {secret\:NAME} in alias URLs'
+        'session:Save and reopen named sets of URLs'
+        'tag:Add, remove and list the tags of aliases'
+        'trash:List or empty the removed aliases kept for web restore'
+        'tree:List aliases as a tree grouped by namespace'
+        'unpin:Unpin aliases, sorting them alphabetically again'
     )
     _describe 'subcommand' subcommands
     _web_aliases
@@ -167,10 +2012,63 @@ _web_first_arg() {
 _web_aliases() {
     local -a aliases
     aliases=("${(@f)$(web _complete-aliases 2>/dev/null)}")
-    [[ -n $aliases ]] && _describe 'alias' aliases
+    # -V keeps the order of _complete-aliases, which lists pinned aliases first
+    [[ -n $aliases ]] && _describe -V 'alias' aliases
+}
+
+_web_groups() {
+    local -a groups
+    groups=("${(@f)$(web _complete-groups 2>/dev/null)}")
+    [[ -n $groups ]] && _describe 'group' groups
+}
+
+_web_recent() {
+    local -a values
+    values=("${(@f)$(web _complete-recent "$1" 2>/dev/null)}")
+    [[ -n $values ]] && _describe -V 'recent value' values
+}
+
+_web_tags() {
+    local -a tags
+    tags=("${(@f)$(web _complete-tags 2>/dev/null)}")
+    [[ -n $tags ]] && _describe 'tag' tags
+}
+
+_web_trash() {
+    local -a trashed
+    trashed=("${(@f)$(web _complete-trash 2>/dev/null)}")
+    [[ -n $trashed ]] && _describe 'removed alias' trashed
+}
+
+_web_sessions() {
+    local -a sessions
+    sessions=("${(@f)$(web _complete-sessions 2>/dev/null)}")
+    [[ -n $sessions ]] && _describe 'session' sessions
+}
+
+_web_browsers() {
+    local -a browsers
+    browsers=("${(@f)$(web _complete-browsers 2>/dev/null)}")
+    [[ -n $browsers ]] && _describe -V 'browser' browsers
 }
 
 _web "$@"
 "#
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_bang_takes_the_other_words_as_arguments() {
+        let line = words("rust !g borrow checker");
+        assert_eq!(parse_bang(&line), Some(("g", words("rust borrow checker"))));
+        assert_eq!(parse_bang(&words("!gh")), Some(("gh", vec![])));
+        assert_eq!(parse_bang(&words("gh ! issues")), None);
+    }
+}
@@ -0,0 +1,146 @@
+//! Informational messages ("Added ...", import summaries), which `--quiet` turns off,
+//! colors, which `--no-color` and `NO_COLOR` turn off, and paging of long listings, which
+//! `--no-pager` turns off.
+
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::trace::trace;
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+static NO_PAGER: AtomicBool = AtomicBool::new(false);
+
+pub fn set_quiet() {
+    QUIET.store(true, Ordering::Relaxed);
+}
+
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Like `println!`, unless --quiet is given.
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if !$crate::output::quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use info;
+
+pub fn set_no_color() {
+    NO_COLOR.store(true, Ordering::Relaxed);
+}
+
+/// Whether to color what is printed: stdout is a terminal, and neither --no-color nor a
+/// non-empty `NO_COLOR` (https://no-color.org) says otherwise.
+pub fn color() -> bool {
+    !NO_COLOR.load(Ordering::Relaxed)
+        && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+        && std::env::var_os("TERM").is_none_or(|t| t != "dumb")
+        && std::io::stdout().is_terminal()
+}
+
+/// How a piece of output is colored.
+#[derive(Clone, Copy)]
+pub enum Style {
+    /// Bold cyan
+    Alias,
+    /// Dimmed
+    Url,
+    /// Bold yellow, for text matching a filter
+    Match,
+}
+
+impl Style {
+    fn code(self) -> &'static str {
+        match self {
+            Style::Alias => "1;36",
+            Style::Url => "2",
+            Style::Match => "1;33",
+        }
+    }
+}
+
+/// `text` in `style`, with each case-insensitive occurrence of `highlight` in
+/// `Style::Match`. Unchanged when not coloring.
+pub fn paint(text: &str, style: Style, highlight: Option<&str>) -> String {
+    if !color() {
+        return text.to_string();
+    }
+    let base = format!("\x1b[{}m", style.code());
+    let mut out = base.clone();
+    match highlight.filter(|h| !h.is_empty()) {
+        Some(highlight) => {
+            // ASCII case folding keeps byte offsets, so matches found in the lowercased
+            // copy can be cut out of `text`.
+            let lower = text.to_ascii_lowercase();
+            let highlight = highlight.to_ascii_lowercase();
+            let mut rest = 0;
+            for (start, _) in lower.match_indices(&highlight) {
+                let end = start + highlight.len();
+                out.push_str(&text[rest..start]);
+                out.push_str(&format!(
+                    "\x1b[0;{}m{}\x1b[0m{}",
+                    Style::Match.code(),
+                    &text[start..end],
+                    base
+                ));
+                rest = end;
+            }
+            out.push_str(&text[rest..]);
+        }
+        None => out.push_str(text),
+    }
+    out.push_str("\x1b[0m");
+    out
+}
+
+pub fn set_no_pager() {
+    NO_PAGER.store(true, Ordering::Relaxed);
+}
+
+/// Print `text`, through `$PAGER` (`less -R` if unset) when stdout is a terminal too short
+/// to show it all, as git does. An empty `$PAGER` or `cat` turns paging off.
+pub fn page(text: &str) {
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less -R".to_string());
+    let fits = || {
+        dialoguer::console::Term::stdout()
+            .size_checked()
+            .is_none_or(|(rows, _)| text.lines().count() < usize::from(rows))
+    };
+    let skip = NO_PAGER.load(Ordering::Relaxed)
+        || matches!(pager.trim(), "" | "cat")
+        || !std::io::stdout().is_terminal()
+        || fits();
+    if skip || !run_pager(&pager, text) {
+        print!("{text}");
+    }
+}
+
+/// Whether `pager` could be run to show `text`.
+fn run_pager(pager: &str, text: &str) -> bool {
+    let mut command = Command::new("sh");
+    command.args(["-c", pager]).stdin(Stdio::piped());
+    // The options git gives less: quit if one screen is enough, pass colors through and
+    // keep the text on screen after quitting.
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            trace!("Failed to run pager `{pager}`: {e}");
+            return false;
+        }
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // Quitting the pager early closes the pipe, which is fine.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    // `sh` exits with 127 when the pager is not found.
+    !matches!(child.wait(), Ok(status) if status.code() == Some(127))
+}
@@ -0,0 +1,101 @@
+//! Interactive alias picker, in the terminal or as a desktop menu.
+
+use anyhow::{Context, Result};
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use crate::browser::{find_in_path, Platform};
+
+/// Desktop menus that read choices on stdin and print the selected line, in order of preference.
+const MENUS: &[&[&str]] = &[
+    &["rofi", "-dmenu", "-i", "-matching", "fuzzy", "-p", "web"],
+    &["fuzzel", "--dmenu", "--prompt", "web> "],
+    &["wofi", "--dmenu", "-i", "-p", "web"],
+    &["dmenu", "-i", "-p", "web"],
+];
+
+/// Let the user choose one of `aliases` (`(alias, url)` pairs). Returns `None` if the
+/// picker was dismissed.
+///
+/// A fuzzy finder is used when running in a terminal; otherwise a desktop menu is
+/// shown, so the picker also works when launched from a hotkey.
+pub fn pick(aliases: &[(String, String)]) -> Result<Option<String>> {
+    if aliases.is_empty() {
+        anyhow::bail!("No aliases registered. Use `web add` to add one.");
+    }
+    if std::io::stdin().is_terminal() && std::io::stderr().is_terminal() {
+        return pick_in_terminal(aliases);
+    }
+    match Platform::detect() {
+        Platform::MacOs => pick_macos(aliases),
+        Platform::Linux | Platform::Wsl => pick_menu(aliases),
+    }
+}
+
+fn rows(aliases: &[(String, String)]) -> Vec<String> {
+    let width = aliases.iter().map(|(a, _)| a.len()).max().unwrap_or(0);
+    aliases
+        .iter()
+        .map(|(alias, url)| format!("{:<width$}  {}", alias, url, width = width))
+        .collect()
+}
+
+fn pick_in_terminal(aliases: &[(String, String)]) -> Result<Option<String>> {
+    let selection = dialoguer::FuzzySelect::new()
+        .with_prompt("Open alias")
+        .items(&rows(aliases))
+        .interact_opt()?;
+    Ok(selection.map(|i| aliases[i].0.clone()))
+}
+
+fn pick_menu(aliases: &[(String, String)]) -> Result<Option<String>> {
+    let menu = MENUS
+        .iter()
+        .find(|menu| find_in_path(menu[0]).is_some())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "No menu program found; install one of: {}",
+                MENUS.iter().map(|m| m[0]).collect::<Vec<_>>().join(", ")
+            )
+        })?;
+    let mut child = Command::new(menu[0])
+        .args(&menu[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", menu[0]))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(rows(aliases).join("\n").as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    // Menus exit non-zero when dismissed with Escape.
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let line = String::from_utf8_lossy(&output.stdout);
+    Ok(line.split_whitespace().next().map(str::to_string))
+}
+
+fn pick_macos(aliases: &[(String, String)]) -> Result<Option<String>> {
+    // Asking the frontmost app to show the dialog keeps it from opening behind other windows.
+    let script = r#"on run argv
+    tell application (path to frontmost application as text)
+        set choice to choose from list argv with title "web" with prompt "Open alias:"
+    end tell
+    if choice is false then return ""
+    return item 1 of choice
+end run"#;
+    let output = Command::new("osascript")
+        .args(["-e", script])
+        .args(aliases.iter().map(|(alias, _)| alias))
+        .output()
+        .context("Failed to run osascript")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "AppleScript failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let choice = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok((!choice.is_empty()).then_some(choice))
+}
@@ -0,0 +1,54 @@
+//! QR codes of URLs, drawn in the terminal or written to PNG files.
+
+use anyhow::{Context, Result};
+use qrcode::render::unicode::Dense1x2;
+use qrcode::{Color, QrCode};
+use std::path::Path;
+
+/// Pixels per module, and modules of blank border that scanners need around the code.
+const PNG_SCALE: usize = 8;
+const QUIET_ZONE: usize = 4;
+
+fn encode(url: &str) -> Result<QrCode> {
+    QrCode::new(url.as_bytes()).with_context(|| format!("Cannot encode {} as a QR code", url))
+}
+
+/// The QR code of `url` as lines of half-block characters, two modules per character.
+/// Drawn light on dark, since most terminals have a dark background.
+pub fn render(url: &str) -> Result<String> {
+    let code = encode(url)?;
+    let mut text = code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build();
+    text.push('\n');
+    Ok(text)
+}
+
+/// Write the QR code of `url` to `path` as a black-on-white grayscale PNG.
+pub fn write_png(url: &str, path: &Path) -> Result<()> {
+    let code = encode(url)?;
+    let width = code.width();
+    let size = (width + 2 * QUIET_ZONE) * PNG_SCALE;
+    let mut pixels = vec![u8::MAX; size * size];
+    for (i, color) in code.to_colors().iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let (x, y) = (i % width + QUIET_ZONE, i / width + QUIET_ZONE);
+        for row in y * PNG_SCALE..(y + 1) * PNG_SCALE {
+            let start = row * size + x * PNG_SCALE;
+            pixels[start..start + PNG_SCALE].fill(0);
+        }
+    }
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -0,0 +1,76 @@
+//! Recently used values of template arguments, offered as prompt defaults and completions.
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Values kept per alias parameter.
+const MAX_VALUES: usize = 10;
+
+/// Alias name to parameter (`query`, `1`, ...) to values, most recent first.
+type Recent = BTreeMap<String, BTreeMap<String, Vec<String>>>;
+
+fn recent_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+    Ok(home.join(".config/web/recent.toml"))
+}
+
+fn load() -> Result<Recent> {
+    let path = recent_path()?;
+    if !path.exists() {
+        return Ok(Recent::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn save(recent: &Recent) -> Result<()> {
+    let path = recent_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!("Failed to create config directory at {}", parent.display())
+        })?;
+    }
+    let content = toml::to_string_pretty(recent).with_context(|| "Failed to serialize")?;
+    std::fs::write(&path, content)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Record `value` as the most recent value of `{param}` for `alias`.
+pub fn remember(alias: &str, param: &str, value: &str) -> Result<()> {
+    let mut recent = load()?;
+    let values = recent
+        .entry(alias.to_string())
+        .or_default()
+        .entry(param.to_string())
+        .or_default();
+    values.retain(|v| v != value);
+    values.insert(0, value.to_string());
+    values.truncate(MAX_VALUES);
+    save(&recent)
+}
+
+/// Recent values of `{param}` for `alias`, most recent first.
+pub fn values(alias: &str, param: &str) -> Result<Vec<String>> {
+    Ok(load()?
+        .remove(alias)
+        .and_then(|mut params| params.remove(param))
+        .unwrap_or_default())
+}
+
+/// Recent values of all parameters of `alias`, without duplicates.
+pub fn all_values(alias: &str) -> Result<Vec<String>> {
+    let mut values: Vec<String> = Vec::new();
+    for value in load()?
+        .remove(alias)
+        .into_iter()
+        .flat_map(|p| p.into_values().flatten())
+    {
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+    Ok(values)
+}
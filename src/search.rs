@@ -0,0 +1,45 @@
+//! `web search`: fuzzy matching of a query against alias names, URLs, tags and descriptions.
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::collections::BTreeMap;
+
+use crate::config::Alias;
+
+/// Matches in the alias name count this many times as much as matches elsewhere.
+const NAME_WEIGHT: i64 = 2;
+
+/// The aliases matching every word of `query` in one of their fields, best match first.
+pub fn rank(aliases: BTreeMap<String, Alias>, query: &[String]) -> Vec<(String, Alias)> {
+    let matcher = SkimMatcherV2::default();
+    let mut ranked: Vec<(i64, String, Alias)> = aliases
+        .into_iter()
+        .filter_map(|(alias, entry)| {
+            let score = score(&matcher, &alias, &entry, query)?;
+            Some((score, alias, entry))
+        })
+        .collect();
+    // Stable, so equally good matches stay in alphabetical order.
+    ranked.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+    ranked
+        .into_iter()
+        .map(|(_, alias, entry)| (alias, entry))
+        .collect()
+}
+
+/// The sum over the words of `query` of the best score of each, or `None` if a word matches
+/// no field.
+fn score(matcher: &SkimMatcherV2, alias: &str, entry: &Alias, query: &[String]) -> Option<i64> {
+    let url = entry.display_url();
+    let mut fields: Vec<&str> = vec![&url];
+    fields.extend(entry.tags.iter().map(String::as_str));
+    fields.extend(entry.description.as_deref());
+    query.iter().try_fold(0, |total, word| {
+        let in_name = matcher.fuzzy_match(alias, word).map(|s| s * NAME_WEIGHT);
+        let elsewhere = fields
+            .iter()
+            .filter_map(|f| matcher.fuzzy_match(f, word))
+            .max();
+        Some(total + in_name.max(elsewhere)?)
+    })
+}
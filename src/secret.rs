@@ -0,0 +1,111 @@
+//! Secrets kept in the OS keychain (macOS) or Secret Service (Linux, via `secret-tool`),
+//! referenced from alias URLs as `{secret:NAME}` so they never appear in the config.
+
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::browser::{find_in_path, Platform};
+
+/// Service name the secrets are stored under.
+const SERVICE: &str = "web";
+
+fn require_secret_tool() -> Result<()> {
+    if find_in_path("secret-tool").is_none() {
+        bail!("secret-tool not found; install libsecret-tools to store secrets");
+    }
+    Ok(())
+}
+
+/// Run `cmd` and return its trimmed stdout, failing with `what` if it exits unsuccessfully.
+fn output(mut cmd: Command, what: &str) -> Result<String> {
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    let output = cmd
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        bail!("{}", what);
+    }
+    let value = String::from_utf8(output.stdout).context("Secret is not valid UTF-8")?;
+    Ok(value.trim_end_matches('\n').to_string())
+}
+
+pub fn set(name: &str, value: &str) -> Result<()> {
+    let status = match Platform::detect() {
+        // `security` only takes the password as an argument; it is briefly visible to
+        // other processes of the same user, but never written to disk in plain text.
+        Platform::MacOs => Command::new("security")
+            .args([
+                "add-generic-password",
+                "-U",
+                "-s",
+                SERVICE,
+                "-a",
+                name,
+                "-w",
+                value,
+            ])
+            .stdout(Stdio::null())
+            .status()
+            .context("Failed to run security")?,
+        Platform::Linux | Platform::Wsl => {
+            require_secret_tool()?;
+            let mut child = Command::new("secret-tool")
+                .args(["store", "--label", &format!("{SERVICE}: {name}")])
+                .args(["service", SERVICE, "account", name])
+                .stdin(Stdio::piped())
+                .spawn()
+                .context("Failed to run secret-tool")?;
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(value.as_bytes())?;
+            }
+            child.wait()?
+        }
+    };
+    if !status.success() {
+        bail!("Failed to store secret '{}'", name);
+    }
+    Ok(())
+}
+
+pub fn get(name: &str) -> Result<String> {
+    let not_found = format!(
+        "Secret '{}' not found; add it with `web secret set {}`",
+        name, name
+    );
+    match Platform::detect() {
+        Platform::MacOs => {
+            let mut cmd = Command::new("security");
+            cmd.args(["find-generic-password", "-s", SERVICE, "-a", name, "-w"]);
+            output(cmd, &not_found)
+        }
+        Platform::Linux | Platform::Wsl => {
+            require_secret_tool()?;
+            let mut cmd = Command::new("secret-tool");
+            cmd.args(["lookup", "service", SERVICE, "account", name]);
+            output(cmd, &not_found)
+        }
+    }
+}
+
+pub fn remove(name: &str) -> Result<()> {
+    let not_found = format!("Secret '{}' not found", name);
+    match Platform::detect() {
+        Platform::MacOs => {
+            let mut cmd = Command::new("security");
+            cmd.args(["delete-generic-password", "-s", SERVICE, "-a", name]);
+            output(cmd, &not_found)?;
+        }
+        Platform::Linux | Platform::Wsl => {
+            // `secret-tool clear` succeeds even when nothing matched.
+            if get(name).is_err() {
+                bail!("{}", not_found);
+            }
+            let mut cmd = Command::new("secret-tool");
+            cmd.args(["clear", "service", SERVICE, "account", name]);
+            output(cmd, &not_found)?;
+        }
+    }
+    Ok(())
+}
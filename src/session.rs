@@ -0,0 +1,92 @@
+//! Named sessions: sets of raw URLs saved to reopen later, kept apart from the alias config.
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Session name to URLs, e.g. `research = ["https://...", "https://..."]`.
+pub type Sessions = BTreeMap<String, Vec<String>>;
+
+pub fn sessions_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+    Ok(home.join(".config/web/sessions.toml"))
+}
+
+pub fn load() -> Result<Sessions> {
+    let path = sessions_path()?;
+    if !path.exists() {
+        return Ok(Sessions::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read sessions file at {}", path.display()))?;
+    toml::from_str(&content).with_context(|| "Failed to parse sessions file")
+}
+
+fn save(sessions: &Sessions) -> Result<()> {
+    let path = sessions_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!("Failed to create config directory at {}", parent.display())
+        })?;
+    }
+    let content =
+        toml::to_string_pretty(sessions).with_context(|| "Failed to serialize sessions")?;
+    std::fs::write(&path, content)
+        .with_context(|| format!("Failed to write sessions file at {}", path.display()))?;
+    Ok(())
+}
+
+/// Save `urls` as session `name`, replacing any session of that name.
+pub fn save_session(name: &str, urls: Vec<String>) -> Result<()> {
+    if urls.is_empty() {
+        anyhow::bail!("Session '{}' needs at least one URL", name);
+    }
+    let mut sessions = load()?;
+    sessions.insert(name.to_string(), urls);
+    save(&sessions)
+}
+
+pub fn remove_session(name: &str) -> Result<()> {
+    let mut sessions = load()?;
+    if sessions.remove(name).is_none() {
+        anyhow::bail!("Session '{}' not found", name);
+    }
+    save(&sessions)
+}
+
+pub fn resolve_session(name: &str) -> Result<Vec<String>> {
+    load()?
+        .remove(name)
+        .ok_or_else(|| anyhow!("Session '{}' not found", name))
+}
+
+/// Read URLs from stdin, one per line. Blank lines and `#` comments are skipped.
+pub fn read_urls_from_stdin() -> Result<Vec<String>> {
+    use std::io::Read;
+    let mut buf = String::new();
+    std::io::stdin()
+        .read_to_string(&mut buf)
+        .with_context(|| "Failed to read from stdin")?;
+    Ok(buf
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+pub fn complete_session(
+    current: &std::ffi::OsStr,
+) -> Vec<clap_complete::engine::CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return vec![];
+    };
+    let Ok(sessions) = load() else {
+        return vec![];
+    };
+    sessions
+        .into_keys()
+        .filter(|name| name.starts_with(current))
+        .map(clap_complete::engine::CompletionCandidate::new)
+        .collect()
+}
@@ -0,0 +1,171 @@
+//! Export aliases as macOS Shortcuts, so they can be run from Spotlight and the
+//! Shortcuts app. Each shortcut opens the alias's URL(s) with the system default browser.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::{self, Config};
+use crate::output::{self, info};
+use crate::template;
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A "URL" action feeding an "Open URLs" action.
+fn open_url_actions(url: &str) -> String {
+    format!(
+        r#"		<dict>
+			<key>WFWorkflowActionIdentifier</key>
+			<string>is.workflow.actions.url</string>
+			<key>WFWorkflowActionParameters</key>
+			<dict>
+				<key>WFURLActionURL</key>
+				<string>{url}</string>
+			</dict>
+		</dict>
+		<dict>
+			<key>WFWorkflowActionIdentifier</key>
+			<string>is.workflow.actions.openurls</string>
+			<key>WFWorkflowActionParameters</key>
+			<dict/>
+		</dict>
+"#,
+        url = escape_xml(url)
+    )
+}
+
+/// Unsigned workflow plist opening each of `urls` in turn.
+fn workflow_plist(urls: &[String]) -> String {
+    let actions: String = urls.iter().map(|url| open_url_actions(url)).collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>WFWorkflowActions</key>
+	<array>
+{actions}	</array>
+	<key>WFWorkflowClientVersion</key>
+	<string>1146.14</string>
+	<key>WFWorkflowHasShortcutInputVariables</key>
+	<false/>
+	<key>WFWorkflowIcon</key>
+	<dict>
+		<key>WFWorkflowIconGlyphNumber</key>
+		<integer>59511</integer>
+		<key>WFWorkflowIconStartColor</key>
+		<integer>463140863</integer>
+	</dict>
+	<key>WFWorkflowImportQuestions</key>
+	<array/>
+	<key>WFWorkflowInputContentItemClasses</key>
+	<array/>
+	<key>WFWorkflowMinimumClientVersion</key>
+	<integer>900</integer>
+	<key>WFWorkflowMinimumClientVersionString</key>
+	<string>900</string>
+	<key>WFWorkflowTypes</key>
+	<array/>
+</dict>
+</plist>
+"#
+    )
+}
+
+/// The URLs a shortcut for `alias` opens: those it resolves to, following `@` links and
+/// filling in `[vars]`. Fails for aliases that can't be opened the same way every time.
+fn shortcut_urls(config: &Config, alias: &str) -> Result<Vec<String>> {
+    let entry = config::find_alias(config, alias)?
+        .with_context(|| format!("Alias '{}' not found", alias))?;
+    if entry.rotate && !entry.urls.is_empty() {
+        anyhow::bail!("it opens a different URL each time (rotate = true)");
+    }
+    let urls = if entry.urls.is_empty() {
+        vec![entry.url]
+    } else {
+        entry.urls
+    };
+    urls.iter()
+        .map(|url| {
+            let url = template::expand_vars(url, &config.vars)?;
+            if template::takes_args(&url) {
+                anyhow::bail!("it needs arguments ({})", url);
+            }
+            if let Some(inner) = template::placeholders(&url)
+                .into_iter()
+                .find(|inner| template::is_computed_placeholder(inner))
+            {
+                anyhow::bail!("{{{}}} is filled in when it is opened", inner);
+            }
+            Ok(url)
+        })
+        .collect()
+}
+
+/// Write one `<alias>.shortcut` file per alias into `dir`.
+///
+/// macOS only imports signed shortcuts, so the files are signed with the `shortcuts`
+/// command when it is available; otherwise the unsigned files are left for the user
+/// to sign on a Mac.
+pub fn export(config: &Config, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+    let can_sign = cfg!(target_os = "macos")
+        && Command::new("shortcuts")
+            .arg("help")
+            .output()
+            .is_ok_and(|o| o.status.success());
+
+    let mut exported = 0;
+    // Wildcard aliases such as `gh/*` only open something with the rest of a name.
+    for alias in config.aliases.keys().filter(|a| !a.contains('*')) {
+        let urls = match shortcut_urls(config, alias) {
+            Ok(urls) => urls,
+            Err(e) => {
+                if !output::quiet() {
+                    eprintln!("Warning: skipped '{}': {:#}", alias, e);
+                }
+                continue;
+            }
+        };
+        let name = alias.replace('/', "-");
+        let target = dir.join(format!("{name}.shortcut"));
+        let plist = workflow_plist(&urls);
+        if can_sign {
+            let unsigned = dir.join(format!("{name}.unsigned.shortcut"));
+            std::fs::write(&unsigned, plist)
+                .with_context(|| format!("Failed to write {}", unsigned.display()))?;
+            let status = Command::new("shortcuts")
+                .args(["sign", "--mode", "anyone", "--input"])
+                .arg(&unsigned)
+                .arg("--output")
+                .arg(&target)
+                .status()
+                .context("Failed to run shortcuts sign")?;
+            std::fs::remove_file(&unsigned).ok();
+            if !status.success() {
+                anyhow::bail!("shortcuts sign failed for '{}'", alias);
+            }
+        } else {
+            std::fs::write(&target, plist)
+                .with_context(|| format!("Failed to write {}", target.display()))?;
+        }
+        exported += 1;
+    }
+
+    info!("Exported {} shortcut(s) to {}.", exported, dir.display());
+    if can_sign {
+        info!("Double-click a .shortcut file to add it to the Shortcuts app.");
+    } else {
+        info!(
+            "These files are unsigned; on a Mac, sign each one before importing:\n  \
+             shortcuts sign --mode anyone --input <file> --output <file>"
+        );
+    }
+    Ok(())
+}
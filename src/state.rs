@@ -0,0 +1,123 @@
+//! Small pieces of state kept between runs, such as the position of rotating aliases and
+//! when each alias was added and last opened.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    /// Alias name to the index of the URL a `rotate` alias opens next
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    rotation: BTreeMap<String, usize>,
+    /// Alias name to when it was added and how often it was opened
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    usage: BTreeMap<String, Usage>,
+}
+
+/// History of one alias, shown by `web info`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Usage {
+    /// When the alias was added with `web add` or `web cp` (RFC 3339); unknown for aliases
+    /// written into the config by hand
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    /// When the alias was last opened (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<String>,
+    /// How many times the alias was opened
+    #[serde(default)]
+    pub count: u64,
+}
+
+fn now() -> String {
+    chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+}
+
+fn state_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+    Ok(home.join(".config/web/state.toml"))
+}
+
+fn load() -> Result<State> {
+    let path = state_path()?;
+    if !path.exists() {
+        return Ok(State::default());
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn save(state: &State) -> Result<()> {
+    let path = state_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!("Failed to create config directory at {}", parent.display())
+        })?;
+    }
+    let content = toml::to_string_pretty(state).with_context(|| "Failed to serialize")?;
+    std::fs::write(&path, content)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Index of the URL `alias` (with `len` URLs) opens this time, advancing it for the next run.
+pub fn next_rotation(alias: &str, len: usize) -> Result<usize> {
+    let mut state = load()?;
+    let position = state.rotation.entry(alias.to_string()).or_default();
+    // The list may have shrunk since the position was stored.
+    let index = *position % len;
+    *position = (index + 1) % len;
+    save(&state)?;
+    Ok(index)
+}
+
+/// Note that `aliases` were just added, forgetting the history of any they replace.
+pub fn record_added(aliases: &[&str]) -> Result<()> {
+    let mut state = load()?;
+    for alias in aliases {
+        state.usage.insert(
+            alias.to_string(),
+            Usage {
+                created: Some(now()),
+                ..Default::default()
+            },
+        );
+    }
+    save(&state)
+}
+
+/// Note that `alias` was just opened.
+pub fn record_use(alias: &str) -> Result<()> {
+    let mut state = load()?;
+    let usage = state.usage.entry(alias.to_string()).or_default();
+    usage.last_used = Some(now());
+    usage.count += 1;
+    save(&state)
+}
+
+/// The history of `alias`, empty if it was never recorded.
+pub fn usage(alias: &str) -> Result<Usage> {
+    Ok(load()?.usage.remove(alias).unwrap_or_default())
+}
+
+/// Carry the state of alias `old` over to `new` after a rename.
+pub fn rename_alias(old: &str, new: &str) -> Result<()> {
+    let mut state = load()?;
+    let rotation = state.rotation.remove(old);
+    let usage = state.usage.remove(old);
+    if rotation.is_none() && usage.is_none() {
+        return Ok(());
+    }
+    match rotation {
+        Some(position) => state.rotation.insert(new.to_string(), position),
+        None => state.rotation.remove(new),
+    };
+    match usage {
+        Some(usage) => state.usage.insert(new.to_string(), usage),
+        None => state.usage.remove(new),
+    };
+    save(&state)
+}
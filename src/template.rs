@@ -0,0 +1,406 @@
+//! Helpers for alias URLs, mainly templates that take arguments from the command line:
+//! `%s` or `{query}` for all the words, `{1}`, `{2}`, ... for single ones. For example
+//! `g = "https://www.google.com/search?q=%s"` makes `web g rust lifetimes` a search.
+
+use anyhow::{anyhow, Result};
+use chrono::format::{Item, StrftimeItems};
+use std::collections::BTreeMap;
+
+pub const QUERY_PLACEHOLDER: &str = "%s";
+
+/// Placeholder forms accepted in argument-taking URLs, for error messages.
+pub const ARGUMENT_PLACEHOLDERS: &str = "%s, {query} or {1}";
+
+/// Parse the contents of a placeholder filled from the command line arguments into the
+/// parameter name and whether the `:raw` modifier turns off percent-encoding.
+fn argument_placeholder(inner: &str) -> Option<(&str, bool)> {
+    let (name, raw) = match inner.strip_suffix(":raw") {
+        Some(name) => (name, true),
+        None => (inner, false),
+    };
+    let valid = name == "query" || (!name.is_empty() && name.bytes().all(|b| b.is_ascii_digit()));
+    valid.then_some((name, raw))
+}
+
+/// Whether `url` takes arguments from the command line.
+pub fn takes_args(url: &str) -> bool {
+    url.contains(QUERY_PLACEHOLDER)
+        || replace_placeholders(url, |inner| {
+            Ok(argument_placeholder(inner).map(|_| String::new()))
+        })
+        .is_ok_and(|filled| filled != url)
+}
+
+/// Fill the argument placeholders of `url` from `args`, percent-encoded so spaces, `#` or
+/// `&` can't break the URL (`{N:raw}` and `{query:raw}` insert them as given). `%s` is
+/// left empty when there are no words; for `{query}` and `{N}`, `ask` is called for a
+/// missing value. Returns the URL and the value used for each `{...}` parameter.
+pub fn fill_args(
+    url: &str,
+    args: &[String],
+    mut ask: impl FnMut(&str) -> Result<String>,
+) -> Result<(String, BTreeMap<String, String>)> {
+    let mut used = BTreeMap::new();
+    let url = url.replace(QUERY_PLACEHOLDER, &percent_encode(&args.join(" ")));
+    let filled = replace_placeholders(&url, |inner| {
+        let Some((name, raw)) = argument_placeholder(inner) else {
+            return Ok(None);
+        };
+        let value = match used.get(name) {
+            Some(value) => String::clone(value),
+            None => {
+                let given = if name == "query" {
+                    (!args.is_empty()).then(|| args.join(" "))
+                } else {
+                    name.parse::<usize>()
+                        .ok()
+                        .and_then(|n| n.checked_sub(1))
+                        .and_then(|i| args.get(i).cloned())
+                };
+                let value = match given {
+                    Some(value) => value,
+                    None => ask(name)?,
+                };
+                used.insert(name.to_string(), value.clone());
+                value
+            }
+        };
+        Ok(Some(if raw { value } else { percent_encode(&value) }))
+    })?;
+    Ok((filled, used))
+}
+
+/// The contents of each `{...}` placeholder in `url`, such as `1:raw` or `vars.org`.
+pub fn placeholders(url: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    // Collecting never fails.
+    let _ = replace_placeholders(url, |inner| {
+        found.push(inner.to_string());
+        Ok(None)
+    });
+    found
+}
+
+/// Whether `inner`, the contents of a placeholder, is filled from command line arguments.
+pub fn is_argument_placeholder(inner: &str) -> bool {
+    argument_placeholder(inner).is_some()
+}
+
+/// Whether `inner`, the contents of a placeholder, is filled when the alias is opened
+/// without arguments: `{vars.NAME}`, `{secret:NAME}`, `{cmd:...}` or a date or clipboard
+/// function.
+pub fn is_computed_placeholder(inner: &str) -> bool {
+    matches!(inner, "date" | "isoweek" | "clipboard")
+        || ["vars.", "secret:", "cmd:", "date:"]
+            .iter()
+            .any(|prefix| inner.starts_with(prefix))
+}
+
+/// Replace each `{...}` placeholder in `url` with what `resolve` returns for its contents.
+/// `None` keeps the placeholder as written, so braces that aren't placeholders survive.
+/// Nested braces are balanced, so `{cmd:awk '{print $1}' f}` is a single placeholder.
+fn replace_placeholders(
+    url: &str,
+    mut resolve: impl FnMut(&str) -> Result<Option<String>>,
+) -> Result<String> {
+    let mut out = String::with_capacity(url.len());
+    let mut rest = url;
+    while let Some(start) = rest.find('{') {
+        let mut depth = 0;
+        let Some(len) = rest[start..].find(|c| {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            depth == 0
+        }) else {
+            break;
+        };
+        let inner = &rest[start + 1..start + len];
+        out.push_str(&rest[..start]);
+        match resolve(inner)? {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Substitute `{vars.NAME}` placeholders with values from the config's `[vars]` table.
+pub fn expand_vars(url: &str, vars: &BTreeMap<String, String>) -> Result<String> {
+    replace_placeholders(url, |inner| {
+        let Some(name) = inner.strip_prefix("vars.") else {
+            return Ok(None);
+        };
+        vars.get(name)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| anyhow!("Unknown variable '{}' in {}", name, url))
+    })
+}
+
+/// Substitute `{secret:NAME}` placeholders with the percent-encoded value `lookup` returns.
+pub fn expand_secrets(url: &str, lookup: impl Fn(&str) -> Result<String>) -> Result<String> {
+    replace_placeholders(url, |inner| match inner.strip_prefix("secret:") {
+        Some(name) => lookup(name).map(|value| Some(percent_encode(&value))),
+        None => Ok(None),
+    })
+}
+
+/// Substitute `{cmd:COMMAND}` placeholders with the percent-encoded output of running
+/// `COMMAND` with `sh -c`. Running commands from the config must be enabled with
+/// `allow_exec = true`.
+pub fn expand_commands(url: &str, allow_exec: bool) -> Result<String> {
+    replace_placeholders(url, |inner| {
+        let Some(command) = inner.strip_prefix("cmd:") else {
+            return Ok(None);
+        };
+        if !allow_exec {
+            anyhow::bail!(
+                "{} runs a command; set `allow_exec = true` in the config to allow it",
+                url
+            );
+        }
+        let output = std::process::Command::new("sh")
+            .args(["-c", command])
+            .stdin(std::process::Stdio::null())
+            .output()
+            .map_err(|e| anyhow!("Failed to run `{}`: {}", command, e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("`{}` failed ({}) {}", command, output.status, stderr.trim());
+        }
+        let value = String::from_utf8_lossy(&output.stdout);
+        Ok(Some(percent_encode(value.trim_end_matches(['\r', '\n']))))
+    })
+}
+
+/// Substitute placeholders computed when the alias is opened: `{date:FORMAT}` with the
+/// local date in a strftime format such as `%Y-%m-%d` (the default for a bare `{date}`),
+/// `{isoweek}` with the ISO week (e.g. `2024-W07`) and `{clipboard}` with the
+/// percent-encoded text `clipboard` returns. Dates are inserted as formatted, so a format
+/// such as `%Y/%m` can add path segments.
+pub fn expand_functions(url: &str, clipboard: impl Fn() -> Result<String>) -> Result<String> {
+    let now = chrono::Local::now();
+    replace_placeholders(url, |inner| {
+        let format = match inner {
+            "clipboard" => return clipboard().map(|text| Some(percent_encode(&text))),
+            "isoweek" => "%G-W%V",
+            "date" => "%Y-%m-%d",
+            _ => match inner.strip_prefix("date:") {
+                Some(format) => format,
+                None => return Ok(None),
+            },
+        };
+        let items: Vec<Item> = StrftimeItems::new(format).collect();
+        if items.contains(&Item::Error) {
+            anyhow::bail!("Invalid date format '{}' in {}", format, url);
+        }
+        Ok(Some(now.format_with_items(items.into_iter()).to_string()))
+    })
+}
+
+/// Check that `url` is a valid absolute URL, with every placeholder filled with a sample value.
+pub fn validate(url: &str) -> Result<()> {
+    let sample = replace_placeholders(&url.replace(QUERY_PLACEHOLDER, "x"), |_| {
+        Ok(Some("x".to_string()))
+    })?;
+    match url::Url::parse(&sample) {
+        Ok(_) => Ok(()),
+        Err(url::ParseError::RelativeUrlWithoutBase) => Err(anyhow!(
+            "'{}' is not a valid URL: it has no scheme (did you mean https://{}?)",
+            url,
+            url
+        )),
+        Err(e) => Err(anyhow!("'{}' is not a valid URL: {}", url, e)),
+    }
+}
+
+/// Append `words` to the path of `url`; each may contain several `/`-separated segments.
+pub fn append_path(url: &str, words: &[String]) -> Result<String> {
+    let mut parsed =
+        url::Url::parse(url).map_err(|e| anyhow!("Cannot append a path to '{}': {}", url, e))?;
+    parsed
+        .path_segments_mut()
+        .map_err(|_| anyhow!("Cannot append a path to '{}'", url))?
+        .pop_if_empty()
+        .extend(
+            words
+                .iter()
+                .flat_map(|word| word.split('/'))
+                .filter(|segment| !segment.is_empty()),
+        );
+    Ok(parsed.into())
+}
+
+/// Set the query parameters `params` on `url`, replacing existing parameters of the same name.
+pub fn set_query_params(url: &str, params: &[(String, String)]) -> Result<String> {
+    let mut parsed =
+        url::Url::parse(url).map_err(|e| anyhow!("Cannot add parameters to '{}': {}", url, e))?;
+    let kept: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(key, _)| !params.iter().any(|(k, _)| k == key))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    parsed
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(kept.iter().chain(params));
+    Ok(parsed.into())
+}
+
+/// Set the `#fragment` of `url`, replacing any it already has.
+pub fn set_fragment(url: &str, fragment: &str) -> Result<String> {
+    let mut parsed =
+        url::Url::parse(url).map_err(|e| anyhow!("Cannot add a fragment to '{}': {}", url, e))?;
+    parsed.set_fragment(Some(fragment.trim_start_matches('#')));
+    Ok(parsed.into())
+}
+
+/// Percent-encode everything except unreserved characters, so the result is safe in
+/// both the path and the query of a URL.
+pub fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Whether `url` starts with a URL scheme such as `https:` or `mailto:`.
+pub fn has_scheme(url: &str) -> bool {
+    let Some((scheme, _)) = url.split_once(':') else {
+        return false;
+    };
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+}
+
+/// Schemes accepted as direct URLs even though they have no `//`.
+const OPAQUE_SCHEMES: &[&str] = &["mailto", "tel", "about", "data"];
+
+/// `input` as a URL to open directly if it is one: a URL with a scheme, or a bare domain
+/// such as `example.com/docs` or `localhost:3000`, which gets `https://` (`http://` for
+/// localhost) prepended.
+pub fn direct_url(input: &str) -> Option<String> {
+    if input.chars().any(char::is_whitespace) {
+        return None;
+    }
+    if has_scheme(input) {
+        let (scheme, rest) = input.split_once(':')?;
+        if rest.starts_with("//") || OPAQUE_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str())
+        {
+            return Some(input.to_string());
+        }
+    }
+    let host = input.split(['/', '?', '#']).next()?;
+    let host = host.rsplit_once(':').map_or(host, |(host, port)| {
+        if port.chars().all(|c| c.is_ascii_digit()) {
+            host
+        } else {
+            ""
+        }
+    });
+    if host == "localhost" {
+        return Some(format!("http://{}", input));
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    let valid_labels = labels.iter().all(|label| {
+        !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+    let tld = labels.last()?;
+    if labels.len() >= 2
+        && valid_labels
+        && tld.len() >= 2
+        && tld.chars().all(|c| c.is_ascii_alphabetic())
+    {
+        return Some(format!("https://{}", input));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_malformed_urls() {
+        assert!(validate("https://example.com/search?q=%s").is_ok());
+        assert!(validate("https://github.com/{1}/{2:raw}").is_ok());
+        let err = validate("example.com").unwrap_err().to_string();
+        assert!(err.contains("did you mean https://example.com"), "{err}");
+        assert!(validate("https://exa mple.com").is_err());
+    }
+
+    #[test]
+    fn fill_args_encodes_unless_raw() {
+        let args = ["rust lang".to_string(), "a/b".to_string()];
+        let (url, used) = fill_args("https://x/{1}/{2:raw}?q=%s", &args, |_| {
+            unreachable!("all arguments are given")
+        })
+        .unwrap();
+        assert_eq!(url, "https://x/rust%20lang/a/b?q=rust%20lang%20a%2Fb");
+        assert_eq!(used["1"], "rust lang");
+    }
+
+    #[test]
+    fn fill_args_asks_for_missing_values_once() {
+        let mut asked = vec![];
+        let (url, _) = fill_args("https://x/{query}/{query}", &[], |name| {
+            asked.push(name.to_string());
+            Ok("a&b".to_string())
+        })
+        .unwrap();
+        assert_eq!(url, "https://x/a%26b/a%26b");
+        assert_eq!(asked, ["query"]);
+    }
+
+    #[test]
+    fn set_query_params_applies_after_arguments_are_filled() {
+        let params = [("hl".to_string(), "en".to_string())];
+        let open = |url: &str, args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            let (url, _) = fill_args(url, &args, |_| unreachable!("all arguments are given"))?;
+            set_query_params(&url, &params)
+        };
+        assert_eq!(
+            open(
+                "https://www.google.com/search?q=%s&hl=de",
+                &["rust", "book"]
+            )
+            .unwrap(),
+            "https://www.google.com/search?q=rust+book&hl=en"
+        );
+        assert_eq!(
+            open("https://github.com/{1}/issues?q={query}", &["rust"]).unwrap(),
+            "https://github.com/rust/issues?q=rust&hl=en"
+        );
+    }
+
+    #[test]
+    fn direct_url_recognizes_urls_and_bare_domains() {
+        assert_eq!(
+            direct_url("example.com/docs").as_deref(),
+            Some("https://example.com/docs")
+        );
+        assert_eq!(
+            direct_url("localhost:3000").as_deref(),
+            Some("http://localhost:3000")
+        );
+        assert_eq!(
+            direct_url("mailto:me@example.com").as_deref(),
+            Some("mailto:me@example.com")
+        );
+        assert_eq!(direct_url("gh"), None);
+        assert_eq!(direct_url("v1.2"), None);
+        assert_eq!(direct_url("two words.com"), None);
+    }
+}
@@ -0,0 +1,38 @@
+//! `--verbose` tracing: how input was resolved to a URL and a browser command, on stderr.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static MUTED: AtomicBool = AtomicBool::new(false);
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed) && !MUTED.load(Ordering::Relaxed)
+}
+
+pub fn write(message: String) {
+    eprintln!("web: {}", message);
+}
+
+/// Run `f` without tracing, for lookups that only check what the input is (an alias that
+/// takes arguments, one with a #fragment, ...) before the lookup that opens it.
+pub fn muted<T>(f: impl FnOnce() -> T) -> T {
+    let was_muted = MUTED.swap(true, Ordering::Relaxed);
+    let result = f();
+    MUTED.store(was_muted, Ordering::Relaxed);
+    result
+}
+
+/// Like `eprintln!`, when --verbose is given; the arguments aren't evaluated otherwise.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::trace::enabled() {
+            $crate::trace::write(format!($($arg)*));
+        }
+    };
+}
+
+pub(crate) use trace;
@@ -0,0 +1,68 @@
+//! `web tree`: aliases grouped by namespace (`work/jira` under `work/`), drawn as a tree.
+
+use std::collections::BTreeMap;
+
+use crate::config::Alias;
+
+/// Aliases of one namespace and the namespaces nested in it.
+#[derive(Default)]
+struct Namespace {
+    /// Last part of the alias name to its URL, in display order
+    aliases: Vec<(String, String)>,
+    children: BTreeMap<String, Namespace>,
+}
+
+impl Namespace {
+    fn insert(&mut self, name: &str, url: String) {
+        match name.split_once('/') {
+            Some((namespace, rest)) if !rest.is_empty() => self
+                .children
+                .entry(format!("{namespace}/"))
+                .or_default()
+                .insert(rest, url),
+            _ => self.aliases.push((name.to_string(), url)),
+        }
+    }
+
+    /// Aliases in this namespace and all nested ones.
+    fn count(&self) -> usize {
+        self.aliases.len() + self.children.values().map(Namespace::count).sum::<usize>()
+    }
+
+    fn render(&self, indent: &str, out: &mut String) {
+        let width = self.aliases.iter().map(|(a, _)| a.len()).max().unwrap_or(0);
+        let mut remaining = self.aliases.len() + self.children.len();
+        let mut branch = |out: &mut String| {
+            remaining -= 1;
+            out.push_str(indent);
+            if remaining == 0 {
+                out.push_str("└── ");
+                format!("{indent}    ")
+            } else {
+                out.push_str("├── ");
+                format!("{indent}│   ")
+            }
+        };
+        for (alias, url) in &self.aliases {
+            branch(out);
+            out.push_str(&format!("{alias:<width$}  {url}\n"));
+        }
+        for (namespace, child) in &self.children {
+            let nested = branch(out);
+            out.push_str(&format!("{} ({})\n", namespace, child.count()));
+            child.render(&nested, out);
+        }
+    }
+}
+
+/// The tree of `aliases` (in display order), below a first line naming `root` and the
+/// number of aliases.
+pub fn render(root: &str, aliases: Vec<(String, Alias)>) -> String {
+    let mut tree = Namespace::default();
+    for (alias, entry) in aliases {
+        tree.insert(&alias, entry.display_url());
+    }
+    let mut out = format!("{} ({})\n", root, tree.count());
+    tree.render("", &mut out);
+    out
+}